@@ -0,0 +1,186 @@
+//headless runner: loads a scene, steps it without a window and writes the results to files
+use nbodyrs::physics::Integrator;
+use nbodyrs::recorder::Recorder;
+use nbodyrs::scene;
+use nbodyrs::simulation::Simulation;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "
+usage: headless <scene.json or scene.csv> (--steps N | --time T) [options]
+
+options:
+    --steps N           number of steps to run
+    --time T            run until the simulation time reaches T
+    --integrator NAME   euler or verlet (default: verlet)
+    --step-size DT      step size (default: 1.0)
+    --output-every N    write diagnostics every N steps (default: 100)
+    --out DIR           output directory (default: current directory)
+    --record FILE       record trajectories to FILE (.csv for text, anything else for binary)
+    --record-every N    record a frame every N steps (default: 1)
+";
+
+enum Duration{
+    Steps(usize),
+    Time(f32),
+}
+
+struct Args{
+    scene: PathBuf,
+    duration: Duration,
+    integrator: Option<Integrator>,
+    step_size: Option<f32>,
+    output_every: usize,
+    out: PathBuf,
+    record: Option<PathBuf>,
+    record_every: usize,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String>{
+    let value = value.ok_or(format!("missing value for {}", flag))?;
+    value.parse::<T>().map_err(|_e| format!("invalid value for {}: {}", flag, value))
+}
+
+fn parse_args() -> Result<Args, String>{
+    let mut args = env::args().skip(1);
+
+    let mut scene = None;
+    let mut duration = None;
+    let mut integrator = None;
+    let mut step_size = None;
+    let mut output_every = 100;
+    let mut out = PathBuf::from(".");
+    let mut record = None;
+    let mut record_every = 1;
+
+    while let Some(arg) = args.next(){
+        match arg.as_str(){
+            "--steps" => duration = Some(Duration::Steps(parse_value(&arg, args.next())?)),
+            "--time" => duration = Some(Duration::Time(parse_value(&arg, args.next())?)),
+            "--integrator" => integrator = Some(parse_value(&arg, args.next())?),
+            "--step-size" => step_size = Some(parse_value(&arg, args.next())?),
+            "--output-every" => output_every = parse_value(&arg, args.next())?,
+            "--out" => out = PathBuf::from(args.next().ok_or("missing value for --out")?),
+            "--record" => record = Some(PathBuf::from(args.next().ok_or("missing value for --record")?)),
+            "--record-every" => record_every = parse_value(&arg, args.next())?,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => scene = Some(PathBuf::from(arg)),
+        }
+    }
+
+    if let (Some(Duration::Time(_)), Some(step_size)) = (&duration, step_size) {
+        if step_size <= 0.0 {
+            return Err("--time needs a positive --step-size".to_string());
+        }
+    }
+
+    Ok(Args{
+        scene: scene.ok_or("no scene given")?,
+        duration: duration.ok_or("one of --steps or --time is required")?,
+        integrator,
+        step_size,
+        output_every: output_every.max(1),
+        out,
+        record,
+        record_every,
+    })
+}
+
+fn write_diagnostics<W: Write>(writer: &mut W, step: usize, sim: &Simulation) -> io::Result<()>{
+    let momentum = sim.momentum();
+    let barycenter = sim.barycenter();
+
+    writeln!(writer, "{},{},{},{},{},{},{},{},{},{}",
+        step,
+        sim.time,
+        sim.bodies.len(),
+        sim.kinetic_energy(),
+        sim.potential_energy(),
+        sim.total_energy(),
+        momentum.x, momentum.y,
+        barycenter.x, barycenter.y)
+}
+
+fn run(args: Args) -> io::Result<()>{
+    let mut scene = scene::load(&args.scene)?;
+    if let Some(integrator) = args.integrator {
+        scene.sim.integrator = integrator;
+    }
+    if let Some(step_size) = args.step_size {
+        scene.sim.step_size = step_size;
+    }
+
+    fs::create_dir_all(&args.out)?;
+    let mut diagnostics = BufWriter::new(File::create(args.out.join("diagnostics.csv"))?);
+    writeln!(diagnostics, "step,time,bodies,kinetic,potential,total,momentum_x,momentum_y,barycenter_x,barycenter_y")?;
+
+    let mut recorder = match &args.record{
+        Some(path) => Some(Recorder::create(path, args.record_every)?),
+        None => None,
+    };
+
+    let sim = &mut scene.sim;
+    let mut step = 0;
+    write_diagnostics(&mut diagnostics, step, sim)?;
+    if let Some(recorder) = &mut recorder {
+        recorder.record(sim)?;
+    }
+
+    let steps = match args.duration{ //counted up front, summing f32 step sizes can stall before reaching the time
+        Duration::Steps(n) => n,
+        Duration::Time(_) if sim.step_size <= 0.0 => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "--time needs a positive step size"));
+        },
+        Duration::Time(t) => ((t - sim.time) / sim.step_size).ceil().max(0.0) as usize,
+    };
+
+    while step < steps{
+        sim.step(1);
+        step += 1;
+
+        if let Some(recorder) = &mut recorder {
+            recorder.record(sim)?;
+        }
+
+        if step % args.output_every == 0 {
+            write_diagnostics(&mut diagnostics, step, sim)?;
+        }
+    }
+
+    if step % args.output_every != 0 {
+        write_diagnostics(&mut diagnostics, step, sim)?;
+    }
+    diagnostics.flush()?;
+    if let Some(recorder) = recorder {
+        recorder.finish()?;
+    }
+
+    println!("ran {} steps to t = {} ({} bodies)", step, sim.time, sim.bodies.len());
+
+    scene::save(args.out.join("final_state.csv"), &scene)?;
+    scene::save(args.out.join("final_state.json"), &scene)?;
+    Ok(())
+}
+
+fn main(){
+    let args = match parse_args(){
+        Ok(args) => args,
+        Err(msg) => {
+            if !msg.is_empty() {
+                eprintln!("error: {}", msg);
+            }
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = run(args){
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
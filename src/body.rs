@@ -1,12 +1,25 @@
-use ggez::nalgebra as na;
+use nalgebra as na;
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 type Point2 = na::Point2<f32>;
 type Vector2 = na::Vector2<f32>;
 
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+pub fn next_id() -> u64{ //unique for the lifetime of the process, so ids survive the vector being rebuilt after merges
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+pub fn reserve_id(id: u64){ //for ids read back from a file, so next_id never hands them out again
+    NEXT_ID.fetch_max(id.saturating_add(1), Ordering::Relaxed);
+}
 
 #[derive(Clone)]
 pub struct Body {
+    pub id: u64,
+    pub name: Option<String>,
+    pub parents: Option<(u64, u64)>, //ids of the two bodies this one merged from
     pub pos: Point2,
     pub mass: f32,
     pub charge: f32,
@@ -17,6 +30,7 @@ pub struct Body {
     pub past_accel: Vector2,
     pub current_accel: Vector2,
     pub collision: Option<usize>,
+    pub anchored: bool, //held in place, still pulls on everything else
 }
 
 impl Body {
@@ -26,6 +40,9 @@ impl Body {
         trail_vec.push_back(Point2::new(position.x, position.y));
 
         Body {
+            id: next_id(),
+            name: None,
+            parents: None,
             pos: position,
             mass: mass_assign,
             charge: charge_assign,
@@ -36,9 +53,15 @@ impl Body {
             past_accel: Vector2::new(0.0, 0.0),
             current_accel: Vector2::new(0.0, 0.0),
             collision: None,
+            anchored: false,
         }
     }
 
+    pub fn with_name(mut self, name: &str) -> Body{
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn update_trail(&mut self){
         self.trail.push_back(self.pos);
 
@@ -52,7 +75,7 @@ impl Body {
 
     pub fn update_euler(&mut self, step_size: f32){
         self.pos += Vector2::new(self.velocity.x * step_size, self.velocity.y * step_size);
-        self.velocity += self.current_accel * step_size.powi(2);
+        self.velocity += self.current_accel * step_size * step_size.abs(); //keeps the sign so negative steps run backwards
     }
 
     pub fn update_verlet(&mut self, step_size: f32){ //verlet velocity
@@ -0,0 +1,63 @@
+use ggez::nalgebra as na;
+
+const TRANSITION_FRAMES: f32 = 45.0; //frames to glide over to a new target
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Follow{
+    Free, //only the pan and zoom move the view
+    Selected,
+    Barycenter,
+    Largest, //most massive body
+}
+
+impl Follow{
+    pub fn next(self) -> Follow{
+        match self{
+            Follow::Free => Follow::Selected,
+            Follow::Selected => Follow::Barycenter,
+            Follow::Barycenter => Follow::Largest,
+            Follow::Largest => Follow::Free,
+        }
+    }
+}
+
+pub struct Camera{
+    pub follow: Follow,
+    pub focus: Vector2, //world point drawn at the pan offset, zero when free
+    target_id: Option<u64>, //body being followed, a change starts a new transition
+    from: Vector2, //focus when the transition started
+    transition: f32, //0 to 1
+}
+
+impl Camera{
+    pub fn new() -> Self{
+        Camera{
+            follow: Follow::Free,
+            focus: Vector2::new(0.0, 0.0),
+            target_id: None,
+            from: Vector2::new(0.0, 0.0),
+            transition: 1.0,
+        }
+    }
+
+    pub fn retarget(&mut self){ //glide from wherever the camera is now
+        self.from = self.focus;
+        self.transition = 0.0;
+    }
+
+    pub fn update(&mut self, id: Option<u64>, target: Option<Point2>){ //target is None when there is nothing to follow, the camera holds still
+        if id != self.target_id {
+            self.target_id = id;
+            self.retarget();
+        }
+
+        if let Some(target) = target {
+            self.transition = (self.transition + 1.0 / TRANSITION_FRAMES).min(1.0);
+            let t = self.transition * self.transition * (3.0 - 2.0 * self.transition); //smoothstep, then locked on
+            self.focus = self.from + (target.coords - self.from) * t;
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::orbit;
+use crate::patterns::{Pattern, VelocityField};
+use crate::physics::acceleration_at;
+use crate::simulation::Simulation;
+use crate::undo::Edit;
+
+use std::collections::HashMap;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BodyField { //what the inspector can edit
+    X,
+    Y,
+    VelocityX,
+    VelocityY,
+    Mass,
+    Radius,
+    Density, //keeps the radius, so the mass changes
+    Charge,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GroupChange { //operations on a whole selection
+    ZeroVelocity,
+    AddVelocity(Vector2),
+    ScaleMass(f32),
+    SetCharge(f32),
+    ToggleAnchor, //anchors the group unless all of it already is
+}
+
+pub fn apply(sim: &mut Simulation, edit: &Edit) { //Params are frontend settings, left to whoever owns them
+    match edit {
+        Edit::AddBodies(bodies) => sim.bodies.extend(bodies.iter().cloned()),
+        Edit::RemoveBodies(bodies) => { //bodies that merged since the edit are already gone
+            let ids = bodies.iter().map(|body| body.id).collect::<Vec<u64>>();
+            sim.remove_bodies(&ids);
+        },
+        Edit::Params{..} => {},
+        Edit::ChangeBodies{after, ..} => {
+            let mut moved = Vec::new();
+            for body in after { //keeps the trail the body has now
+                if let Some(i) = sim.bodies.iter().position(|old| old.id == body.id) {
+                    let old = &mut sim.bodies[i];
+                    if old.pos != body.pos {
+                        moved.push(i);
+                    }
+                    old.pos = body.pos;
+                    old.velocity = body.velocity;
+                    old.mass = body.mass;
+                    old.charge = body.charge;
+                    old.radius = body.radius;
+                    old.anchored = body.anchored;
+                    old.name = body.name.clone();
+                }
+            }
+            for i in moved { //position edits from the inspector and undoing drags
+                reset_accel(sim, i);
+            }
+        },
+    }
+}
+
+pub fn reset_accel(sim: &mut Simulation, i: usize) { //for a body moved by hand, so Verlet's next step doesn't use the acceleration from where it was
+    let body = &sim.bodies[i];
+    let accel = acceleration_at(&sim.bodies, body.pos, body.mass, body.charge, body.radius, sim.forces);
+    sim.bodies[i].past_accel = accel;
+}
+
+pub fn move_body(sim: &mut Simulation, i: usize, pos: Point2) { //drags, which aren't undoable until they're released
+    let body = &mut sim.bodies[i];
+    body.pos = pos;
+    body.trail.clear(); //no line back to where it was
+    body.trail.push_back(pos);
+    reset_accel(sim, i);
+}
+
+pub fn set_field(body: &Body, field: BodyField, value: f32) -> Result<Body, &'static str> { //the body with one field changed
+    if (field == BodyField::Mass || field == BodyField::Density) && value <= 0.0 { //charged bodies divide by their mass
+        return Err("Mass and density have to be positive");
+    }
+
+    let mut after = body.clone();
+    match field {
+        BodyField::X => after.pos.x = value,
+        BodyField::Y => after.pos.y = value,
+        BodyField::VelocityX => after.velocity.x = value,
+        BodyField::VelocityY => after.velocity.y = value,
+        BodyField::Mass => after.mass = value,
+        BodyField::Radius => after.radius = value.max(0.1), //keeps the mass, so the density changes
+        BodyField::Density => after.mass = value * after.radius.powi(3),
+        BodyField::Charge => after.charge = value,
+    }
+    Ok(after)
+}
+
+pub fn rename(body: &Body, name: &str) -> Body { //an empty name clears it
+    let mut after = body.clone();
+    after.name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+    after
+}
+
+pub fn find_bodies(sim: &Simulation, ids: &[u64]) -> Vec<Body> {
+    sim.bodies.iter().filter(|body| ids.contains(&body.id)).cloned().collect()
+}
+
+pub fn change_group(sim: &Simulation, ids: &[u64], change: GroupChange) -> Result<Edit, &'static str> { //one undoable edit for the whole group
+    if let GroupChange::ScaleMass(scale) = change {
+        if scale <= 0.0 {
+            return Err("The mass scale has to be positive");
+        }
+    }
+
+    let before = find_bodies(sim, ids);
+    let anchor = !before.iter().all(|body| body.anchored);
+    let after = before.iter()
+        .cloned()
+        .map(|mut body| {
+            match change {
+                GroupChange::ZeroVelocity => body.velocity = Vector2::new(0.0, 0.0),
+                GroupChange::AddVelocity(velocity) => body.velocity += velocity,
+                GroupChange::ScaleMass(scale) => body.mass *= scale,
+                GroupChange::SetCharge(charge) => body.charge = charge,
+                GroupChange::ToggleAnchor => {
+                    body.anchored = anchor;
+                    if anchor {
+                        body.velocity = Vector2::new(0.0, 0.0);
+                    }
+                },
+            }
+            body
+        })
+        .collect();
+
+    Ok(Edit::ChangeBodies{before, after})
+}
+
+pub fn merged_into(sim: &Simulation) -> HashMap<u64, u64> { //ids of merged bodies to the body they became
+    sim.bodies.iter()
+        .filter_map(|body| body.parents.map(|(a, b)| [(a, body.id), (b, body.id)]))
+        .flatten()
+        .collect()
+}
+
+pub fn follow(merged_into: &HashMap<u64, u64>, id: u64) -> u64 {
+    merged_into.get(&id).cloned().unwrap_or(id)
+}
+
+pub fn follow_all(merged_into: &HashMap<u64, u64>, ids: &[u64]) -> Vec<u64> { //keeps the order, both halves of a merge become one id
+    let mut followed = Vec::with_capacity(ids.len());
+    for id in ids.iter().map(|id| follow(merged_into, *id)) {
+        if !followed.contains(&id) {
+            followed.push(id);
+        }
+    }
+    followed
+}
+
+pub fn spawn_pattern(sim: &Simulation, pattern: &Pattern, template: &Body, primary: Option<u64>, seed: u64) -> Vec<Body> { //around template's position, orbiting primary if there is one
+    let primary = primary.and_then(|id| sim.bodies.iter().position(|body| body.id == id));
+    let center_body = match pattern.velocity {
+        VelocityField::Zero | VelocityField::Uniform => None,
+        VelocityField::Rotation => primary,
+        VelocityField::Keplerian => primary.or_else(|| orbit::dominant_attractor_at(&sim.bodies, template.pos)),
+    };
+
+    pattern.spawn(template, center_body.map(|i| &sim.bodies[i]), sim.forces.g, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sim() -> Simulation {
+        Simulation::new(vec![
+            Body::new(Point2::new(0.0, 0.0), 1000.0, 0.0, 10.0, Vector2::new(0.0, 0.0)),
+            Body::new(Point2::new(100.0, 0.0), 1.0, 0.0, 1.0, Vector2::new(0.0, 3.0)),
+        ])
+    }
+
+    #[test]
+    fn edits_undo_cleanly() {
+        let mut sim = sim();
+        let id = sim.bodies[1].id;
+        let moved = set_field(&sim.bodies[1], BodyField::X, 200.0).unwrap();
+        let edit = Edit::ChangeBodies{before: vec![sim.bodies[1].clone()], after: vec![moved]};
+
+        apply(&mut sim, &edit);
+        assert_eq!(sim.bodies[1].pos.x, 200.0);
+        assert_eq!(sim.bodies[1].past_accel, acceleration_at(&sim.bodies, sim.bodies[1].pos, 1.0, 0.0, 1.0, sim.forces));
+
+        apply(&mut sim, &edit.inverse());
+        assert_eq!(sim.bodies[1].pos.x, 100.0);
+
+        let removed = Edit::RemoveBodies(find_bodies(&sim, &[id]));
+        apply(&mut sim, &removed);
+        assert_eq!(sim.bodies.len(), 1);
+        apply(&mut sim, &removed.inverse());
+        assert_eq!(sim.bodies[1].id, id);
+    }
+
+    #[test]
+    fn rejects_non_positive_masses() {
+        let sim = sim();
+        assert!(set_field(&sim.bodies[1], BodyField::Mass, 0.0).is_err());
+        assert!(set_field(&sim.bodies[1], BodyField::Density, -1.0).is_err());
+        assert!(change_group(&sim, &[sim.bodies[1].id], GroupChange::ScaleMass(0.0)).is_err());
+        assert_eq!(set_field(&sim.bodies[1], BodyField::Density, 2.0).unwrap().mass, 2.0);
+    }
+
+    #[test]
+    fn anchoring_toggles_the_group() {
+        let mut sim = sim();
+        let ids = sim.bodies.iter().map(|body| body.id).collect::<Vec<u64>>();
+        sim.bodies[0].anchored = true;
+
+        let edit = change_group(&sim, &ids, GroupChange::ToggleAnchor).unwrap();
+        apply(&mut sim, &edit);
+        assert!(sim.bodies.iter().all(|body| body.anchored && body.velocity == Vector2::new(0.0, 0.0)));
+
+        let edit = change_group(&sim, &ids, GroupChange::ToggleAnchor).unwrap();
+        apply(&mut sim, &edit);
+        assert!(sim.bodies.iter().all(|body| !body.anchored));
+    }
+
+    #[test]
+    fn merges_are_followed() {
+        let mut sim = sim();
+        let ids = sim.bodies.iter().map(|body| body.id).collect::<Vec<u64>>();
+        sim.bodies[1].pos = Point2::new(5.0, 0.0);
+        sim.step(1);
+        assert_eq!(sim.bodies.len(), 1);
+
+        let merged_into = merged_into(&sim);
+        assert_eq!(follow_all(&merged_into, &ids), vec![sim.bodies[0].id]);
+        assert_eq!(follow(&merged_into, 12345), 12345);
+    }
+
+    #[test]
+    fn keplerian_patterns_orbit_the_primary() {
+        let sim = sim();
+        let pattern = Pattern{velocity: VelocityField::Keplerian, ..Pattern::default()};
+        let template = Body::new(Point2::new(0.0, 200.0), 1.0, 0.0, 1.0, Vector2::new(0.0, 0.0));
+
+        let bodies = spawn_pattern(&sim, &pattern, &template, None, 0);
+        assert_eq!(bodies.len(), pattern.count());
+        assert!(bodies.iter().all(|body| body.velocity.norm() > 0.0));
+    }
+}
@@ -0,0 +1,141 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::frame::RotatingFrame;
+use crate::physics::{acceleration_at, distance, Forces};
+
+use rayon::prelude::*;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+pub fn potential(bodies: &[Body], pos: Point2, mass: f32, charge: f32, forces: Forces) -> f32 {
+    //potential energy per unit mass of a test body at pos, gravitational plus electric,
+    //inside a body it's clamped to the value at the surface
+    bodies.iter()
+        .map(|body| {
+            let r = distance(body.pos, pos).max(body.radius);
+            let electric = if mass != 0.0 {forces.k * body.charge * charge / mass} else {0.0};
+            (electric - forces.g * body.mass) / r
+        })
+        .sum()
+}
+
+pub fn effective_potential(bodies: &[Body], pos: Point2, mass: f32, charge: f32, forces: Forces, frame: &RotatingFrame) -> f32 {
+    //potential felt in a rotating frame, its saddles and peaks are the Lagrange points
+    potential(bodies, pos, mass, charge, forces) + frame.centrifugal_potential(pos)
+}
+
+pub fn acceleration(bodies: &[Body], pos: Point2, mass: f32, charge: f32, forces: Forces) -> Vector2 { //field felt by a point-sized test body
+    acceleration_at(bodies, pos, mass, charge, 0.0, forces)
+}
+
+pub fn streamline(bodies: &[Body], start: Point2, mass: f32, charge: f32, forces: Forces, step: f32, max_steps: usize) -> Vec<Point2> {
+    //follows the field from start in steps of equal length, backwards if step is negative,
+    //until it runs into a body or the field vanishes
+    let mut line = vec![start];
+    let mut pos = start;
+
+    for _ in 0..max_steps {
+        let direction = acceleration(bodies, pos, mass, charge, forces);
+        let norm = direction.norm();
+        if !norm.is_normal() { //zero, or infinite right on top of a body
+            break;
+        }
+
+        //midpoint step, so lines curving around a body don't spiral off
+        let half = pos + direction / norm * step / 2.0;
+        let direction = acceleration(bodies, half, mass, charge, forces);
+        let norm = direction.norm();
+        if !norm.is_normal() { //zero, or infinite right on top of a body
+            break;
+        }
+
+        pos += direction / norm * step;
+        line.push(pos);
+
+        if bodies.iter().any(|body| distance(body.pos, pos) <= body.radius) {
+            break;
+        }
+    }
+
+    line
+}
+
+pub struct Grid { //values sampled on the corners of square cells
+    pub origin: Point2,
+    pub cell: f32,
+    pub columns: usize,
+    pub rows: usize,
+    pub values: Vec<f32>,
+}
+
+impl Grid {
+    pub fn sample<F: Fn(Point2) -> f32 + Sync>(origin: Point2, cell: f32, columns: usize, rows: usize, f: F) -> Grid {
+        let values = (0..rows * columns)
+            .into_par_iter()
+            .map(|i| f(origin + na::Vector2::new((i % columns) as f32, (i / columns) as f32) * cell))
+            .collect();
+
+        Grid {
+            origin,
+            cell,
+            columns,
+            rows,
+            values,
+        }
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> f32 {
+        self.values[row * self.columns + column]
+    }
+
+    pub fn point(&self, column: f32, row: f32) -> Point2 {
+        self.origin + na::Vector2::new(column, row) * self.cell
+    }
+
+    pub fn percentile(&self, fraction: f32) -> f32 { //for colour scales that ignore the spikes at each body
+        let mut sorted = self.values.iter().cloned().filter(|value| value.is_finite()).collect::<Vec<f32>>();
+        if sorted.is_empty() {
+            return 0.0;
+        }
+
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted[((sorted.len() - 1) as f32 * fraction.clamp(0.0, 1.0)).round() as usize]
+    }
+
+    pub fn contour(&self, level: f32) -> Vec<[Point2; 2]> { //marching squares, line segments where the values cross level
+        let mut segments = Vec::new();
+
+        for row in 0..self.rows.saturating_sub(1) {
+            for column in 0..self.columns.saturating_sub(1) {
+                let corners = [
+                    (0.0, 0.0, self.get(column, row)),
+                    (1.0, 0.0, self.get(column + 1, row)),
+                    (1.0, 1.0, self.get(column + 1, row + 1)),
+                    (0.0, 1.0, self.get(column, row + 1)),
+                ];
+
+                //where level crosses each edge of the cell, going round the corners
+                let crossings = (0..4)
+                    .filter_map(|i| {
+                        let (x1, y1, a) = corners[i];
+                        let (x2, y2, b) = corners[(i + 1) % 4];
+                        if (a < level) == (b < level) || a == b {
+                            return None;
+                        }
+
+                        let t = (level - a) / (b - a);
+                        Some(self.point(column as f32 + x1 + (x2 - x1) * t, row as f32 + y1 + (y2 - y1) * t))
+                    })
+                    .collect::<Vec<Point2>>();
+
+                //two crossings is one segment, four is a saddle which is split arbitrarily
+                for pair in crossings.chunks(2).filter(|pair| pair.len() == 2) {
+                    segments.push([pair[0], pair[1]]);
+                }
+            }
+        }
+
+        segments
+    }
+}
@@ -0,0 +1,119 @@
+use nalgebra as na;
+use crate::simulation::Simulation;
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+type Point2 = na::Point2<f32>;
+
+pub struct Forecast { //where every body will be over the next steps
+    pub time: f32, //simulation time the forecast starts from
+    pub paths: Vec<(u64, Vec<Point2>)>, //body id and its positions, bodies made by merges get their own path
+}
+
+pub fn forecast(sim: &Simulation, steps: usize) -> Forecast {
+    let mut world = sim.clone();
+    world.trail_length = 2; //only the paths are needed
+
+    let mut paths: Vec<(u64, Vec<Point2>)> = world.bodies.iter().map(|body| (body.id, vec![body.pos])).collect();
+    let mut index: HashMap<u64, usize> = paths.iter().enumerate().map(|(i, (id, _))| (*id, i)).collect();
+
+    for _ in 0..steps {
+        world.step(1);
+
+        for body in world.bodies.iter() {
+            let i = *index.entry(body.id).or_insert_with(|| {
+                paths.push((body.id, Vec::new()));
+                paths.len() - 1
+            });
+            paths[i].1.push(body.pos);
+        }
+    }
+
+    Forecast {
+        time: sim.time,
+        paths,
+    }
+}
+
+fn same_world(a: &Simulation, b: &Simulation) -> bool { //false if anything a forecast depends on has changed
+    a.time == b.time
+        && a.step_size == b.step_size
+        && a.integrator == b.integrator
+        && a.forces == b.forces
+        && a.bodies.len() == b.bodies.len()
+        && a.bodies.iter().zip(b.bodies.iter()).all(|(a, b)| {
+            a.id == b.id && a.pos == b.pos && a.velocity == b.velocity && a.mass == b.mass && a.charge == b.charge && a.radius == b.radius
+        })
+}
+
+pub struct Forecaster { //runs forecasts on a background thread so the frontend never waits for one
+    requests: Sender<(u64, Simulation, usize)>,
+    results: Receiver<(u64, Forecast)>,
+    requested: Option<(Simulation, usize)>, //the last world sent off
+    pending: bool,
+    generation: u64, //bumped by clear(), results from before it are thrown away
+    pub latest: Option<Forecast>,
+}
+
+impl Forecaster {
+    pub fn new() -> Self {
+        let (requests, worker_requests) = mpsc::channel::<(u64, Simulation, usize)>();
+        let (worker_results, results) = mpsc::channel();
+
+        thread::spawn(move || {
+            while let Ok(mut request) = worker_requests.recv() {
+                while let Ok(newer) = worker_requests.try_recv() { //skip straight to the newest world
+                    request = newer;
+                }
+
+                let (generation, sim, steps) = request;
+                if worker_results.send((generation, forecast(&sim, steps))).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Forecaster {
+            requests,
+            results,
+            requested: None,
+            pending: false,
+            generation: 0,
+            latest: None,
+        }
+    }
+
+    pub fn refresh(&mut self, sim: &Simulation, steps: usize) { //collect a finished forecast and start a new one if the world changed
+        while let Ok((generation, forecast)) = self.results.try_recv() {
+            if generation == self.generation {
+                self.latest = Some(forecast);
+                self.pending = false;
+            }
+        }
+
+        let changed = match &self.requested {
+            Some((requested, requested_steps)) => *requested_steps != steps || !same_world(requested, sim),
+            None => true,
+        };
+
+        if changed && !self.pending {
+            self.requested = Some((sim.clone(), steps));
+            self.pending = self.requests.send((self.generation, sim.clone(), steps)).is_ok();
+        }
+    }
+
+    pub fn clear(&mut self) { //a forecast still being worked on is dropped when it arrives
+        self.requested = None;
+        self.latest = None;
+        self.pending = false;
+        self.generation += 1;
+    }
+}
+
+impl Default for Forecaster {
+    fn default() -> Self {
+        Forecaster::new()
+    }
+}
@@ -0,0 +1,94 @@
+use nalgebra as na;
+use crate::body::Body;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Debug, Copy, Clone)]
+pub struct RotatingFrame { //a frame turning at a constant rate about a point moving in a straight line
+    pub center: Point2,
+    pub velocity: Vector2,
+    pub omega: f32, //radians per time unit, positive from +x towards +y
+}
+
+impl RotatingFrame {
+    pub fn co_rotating(primary: &Body, secondary: &Body) -> Option<RotatingFrame> { //turning with the pair, about their barycentre
+        let total_mass = primary.mass + secondary.mass;
+        let r = secondary.pos - primary.pos;
+        if total_mass == 0.0 || r.norm_squared() == 0.0 {
+            return None;
+        }
+
+        let v = secondary.velocity - primary.velocity;
+
+        Some(RotatingFrame {
+            center: Point2::from((primary.pos.coords * primary.mass + secondary.pos.coords * secondary.mass) / total_mass),
+            velocity: (primary.velocity * primary.mass + secondary.velocity * secondary.mass) / total_mass,
+            omega: (r.x * v.y - r.y * v.x) / r.norm_squared(),
+        })
+    }
+
+    pub fn velocity_at(&self, pos: Point2) -> Vector2 { //of a point standing still in the frame
+        let r = pos - self.center;
+        self.velocity + Vector2::new(-r.y, r.x) * self.omega
+    }
+
+    pub fn centrifugal_potential(&self, pos: Point2) -> f32 { //per unit mass
+        -0.5 * self.omega.powi(2) * (pos - self.center).norm_squared()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrameAxes { //where a pair's barycentre is and which way the line from primary to secondary points, at one instant
+    pub center: Point2,
+    pub angle: f32, //radians from the +x axis
+}
+
+impl FrameAxes {
+    pub fn new(primary: Point2, primary_mass: f32, secondary: Point2, secondary_mass: f32) -> Option<FrameAxes> {
+        let total_mass = primary_mass + secondary_mass;
+        let r = secondary - primary;
+        if total_mass == 0.0 || r.norm_squared() == 0.0 {
+            return None;
+        }
+
+        Some(FrameAxes {
+            center: Point2::from((primary.coords * primary_mass + secondary.coords * secondary_mass) / total_mass),
+            angle: r.y.atan2(r.x),
+        })
+    }
+
+    pub fn between(primary: &Body, secondary: &Body) -> Option<FrameAxes> {
+        FrameAxes::new(primary.pos, primary.mass, secondary.pos, secondary.mass)
+    }
+
+    pub fn rotate_in(&self, v: Vector2) -> Vector2 { //a world direction in these axes
+        let (sin, cos) = self.angle.sin_cos();
+        Vector2::new(v.x * cos + v.y * sin, -v.x * sin + v.y * cos)
+    }
+
+    pub fn rotate_out(&self, v: Vector2) -> Vector2 { //a direction in these axes back in the world
+        let (sin, cos) = self.angle.sin_cos();
+        Vector2::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+    }
+
+    pub fn to_frame(&self, pos: Point2) -> Point2 { //barycentre at the origin, secondary on the +x axis
+        Point2::from(self.rotate_in(pos - self.center))
+    }
+
+    pub fn from_frame(&self, pos: Point2) -> Point2 {
+        self.center + self.rotate_out(pos.coords)
+    }
+}
+
+pub fn co_rotating_path(path: &[Point2], primary: &[Point2], secondary: &[Point2], masses: (f32, f32), anchor: &FrameAxes) -> Vec<Point2> {
+    //each point moved into the axes the pair had at the same step, then laid back down along the anchor's axes,
+    //so the pair itself stays put. The three paths end at the same step and points older than the pair's are dropped
+    let mut moved = path.iter().rev()
+        .zip(primary.iter().rev().zip(secondary.iter().rev()))
+        .filter_map(|(pos, (p, s))| FrameAxes::new(*p, masses.0, *s, masses.1).map(|axes| anchor.from_frame(axes.to_frame(*pos))))
+        .collect::<Vec<Point2>>();
+
+    moved.reverse();
+    moved
+}
@@ -0,0 +1,264 @@
+use nalgebra as na;
+use crate::body::Body;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DiskParams {
+    pub bodies: usize, //disk bodies, not counting the bulge
+    pub disk_mass: f32,
+    pub bulge_mass: f32,
+    pub bulge_radius: f32,
+    pub scale_length: f32, //surface density falls off as e^(-r/scale_length)
+    pub max_radius: f32,
+    pub body_radius: f32,
+}
+
+impl Default for DiskParams {
+    fn default() -> Self {
+        DiskParams {
+            bodies: 300,
+            disk_mass: 1_000.0,
+            bulge_mass: 30_000.0,
+            bulge_radius: 10.0,
+            scale_length: 70.0,
+            max_radius: 250.0,
+            body_radius: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionParams {
+    pub first: DiskParams,
+    pub second: DiskParams,
+    pub inclination: f32, //radians between the two disks, past PI/2 the second disk counter-rotates
+    pub impact_parameter: f32,
+    pub separation: f32, //starting distance between the bulges
+}
+
+impl Default for CollisionParams {
+    fn default() -> Self {
+        let disk = DiskParams {
+            bodies: 200,
+            disk_mass: 700.0,
+            bulge_mass: 25_000.0,
+            max_radius: 180.0,
+            scale_length: 50.0,
+            ..DiskParams::default()
+        };
+
+        CollisionParams {
+            first: disk,
+            second: disk,
+            inclination: 0.0,
+            impact_parameter: 250.0,
+            separation: 900.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ClusterParams {
+    pub bodies: usize,
+    pub mass: f32, //total, split evenly between the bodies
+    pub scale_radius: f32, //Plummer radius, or the edge of a cold collapse
+    pub body_radius: f32,
+}
+
+impl Default for ClusterParams {
+    fn default() -> Self {
+        ClusterParams {
+            bodies: 200,
+            mass: 20_000.0,
+            scale_radius: 100.0,
+            body_radius: 1.0,
+        }
+    }
+}
+
+fn random_direction(rng: &mut StdRng) -> Vector2 {
+    let angle = rng.gen_range(0.0, 2.0 * PI);
+    Vector2::new(angle.cos(), angle.sin())
+}
+
+fn projected_direction(rng: &mut StdRng) -> Vector2 { //the x and y components of a random direction in 3D
+    let z: f32 = rng.gen_range(-1.0, 1.0);
+    random_direction(rng) * (1.0 - z * z).sqrt()
+}
+
+fn spaced_offsets<F: FnMut(&mut StdRng) -> Vector2>(rng: &mut StdRng, count: usize, spacing: f32, mut sample: F) -> Vec<Vector2> {
+    //spaced out so no two bodies start touching and merge straight away,
+    //giving up on the spacing for a body after 100 tries if it's too crowded
+    let mut offsets: Vec<Vector2> = Vec::with_capacity(count);
+    let mut attempts = 0;
+
+    while offsets.len() < count {
+        let offset = sample(rng);
+
+        attempts += 1;
+        if attempts > 100 || offsets.iter().all(|other| (other - offset).norm() > spacing) {
+            offsets.push(offset);
+            attempts = 0;
+        }
+    }
+
+    offsets
+}
+
+fn sample_disk_radius(rng: &mut StdRng, params: &DiskParams) -> f32 {
+    //the radii of an exponential disk follow a gamma(2) distribution, the sum of two exponentials,
+    //redrawn until they land between the bulge and the edge
+    let min_radius = params.bulge_radius * 3.0; //inner orbits get too fast to integrate right next to the bulge
+
+    loop {
+        let r = -params.scale_length * (rng.gen_range(f32::EPSILON, 1.0) * rng.gen_range(f32::EPSILON, 1.0)).ln();
+        if r >= min_radius && r <= params.max_radius {
+            return r;
+        }
+    }
+}
+
+fn disk(rng: &mut StdRng, center: Point2, velocity: Vector2, params: &DiskParams, inclination: f32, g: f32) -> Vec<Body> {
+    let body_mass = params.disk_mass / params.bodies as f32;
+
+    let mut offsets = spaced_offsets(rng, params.bodies, params.body_radius * 4.0, |rng| {
+        let r = sample_disk_radius(rng, params);
+        random_direction(rng) * r
+    });
+    offsets.sort_by(|a, b| a.norm().partial_cmp(&b.norm()).unwrap());
+
+    //the sandbox is 2D, so tilting a disk means flattening it along y (cos of the inclination),
+    //which mirrors it into a counter-rotating disk once the inclination passes 90 degrees
+    let tilt = Vector2::new(1.0, inclination.cos());
+
+    let mut bodies = vec![Body::new(center, params.bulge_mass, 0.0, params.bulge_radius, velocity)];
+
+    for (i, offset) in offsets.iter().enumerate() {
+        let r = offset.norm();
+        let enclosed = params.bulge_mass + body_mass * i as f32; //bodies further in, sorted by radius
+        let speed = (g * enclosed / r).sqrt();
+        let direction = offset / r;
+
+        bodies.push(Body::new(
+            center + offset.component_mul(&tilt),
+            body_mass,
+            0.0,
+            params.body_radius,
+            velocity + Vector2::new(-direction.y, direction.x).component_mul(&tilt) * speed));
+    }
+
+    bodies
+}
+
+pub fn galaxy_disk(center: Point2, params: &DiskParams, g: f32, seed: u64) -> Vec<Body> {
+    //a rotating disk around a central bulge at rest, the orbits are perfectly circular so a heavy disk is cold and clumps up
+    let mut rng = StdRng::seed_from_u64(seed);
+    disk(&mut rng, center, Vector2::new(0.0, 0.0), params, 0.0, g)
+}
+
+pub fn galaxy_collision(center: Point2, params: &CollisionParams, g: f32, seed: u64) -> Vec<Body> {
+    //two disks falling towards each other on a parabolic orbit, offset by the impact parameter,
+    //in the frame of their common centre of mass
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mass1 = params.first.bulge_mass + params.first.disk_mass;
+    let mass2 = params.second.bulge_mass + params.second.disk_mass;
+    let total = mass1 + mass2;
+
+    let distance = params.separation.max(params.impact_parameter.abs());
+    let along = (distance.powi(2) - params.impact_parameter.powi(2)).sqrt();
+    let offset = Vector2::new(along, params.impact_parameter); //second galaxy relative to the first
+
+    let speed = (2.0 * g * total / distance).sqrt();
+    let relative_velocity = Vector2::new(-speed, 0.0);
+
+    let mut bodies = disk(&mut rng,
+        center - offset * (mass2 / total),
+        -relative_velocity * (mass2 / total),
+        &params.first, 0.0, g);
+
+    bodies.extend(disk(&mut rng,
+        center + offset * (mass1 / total),
+        relative_velocity * (mass1 / total),
+        &params.second, params.inclination, g));
+
+    bodies
+}
+
+fn virialize(bodies: &mut [Body], g: f32) { //scale velocities about the barycentre until 2K = -W
+    let total_mass: f32 = bodies.iter().map(|body| body.mass).sum();
+    let drift = bodies.iter().fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.velocity * body.mass) / total_mass;
+
+    let kinetic: f32 = bodies.iter().map(|body| 0.5 * body.mass * (body.velocity - drift).norm_squared()).sum();
+    let mut potential = 0.0;
+    for (i, body1) in bodies.iter().enumerate() {
+        for body2 in bodies.iter().skip(i + 1) {
+            let r = (body1.pos - body2.pos).norm();
+            if r > 0.0 {
+                potential -= g * body1.mass * body2.mass / r;
+            }
+        }
+    }
+
+    if kinetic > 0.0 {
+        let scale = (-potential / (2.0 * kinetic)).sqrt();
+        bodies.iter_mut().for_each(|body| body.velocity = drift + (body.velocity - drift) * scale);
+    }
+}
+
+pub fn plummer(center: Point2, params: &ClusterParams, g: f32, seed: u64) -> Vec<Body> {
+    //Aarseth, Henon & Wielen (1974) sampling of a 3D Plummer sphere, projected onto the plane.
+    //The projected cluster feels 2D distances, so the velocities are rescaled to virial equilibrium afterwards
+    let mut rng = StdRng::seed_from_u64(seed);
+    let a = params.scale_radius;
+    let body_mass = params.mass / params.bodies as f32;
+
+    let radii = spaced_offsets(&mut rng, params.bodies, params.body_radius * 4.0, |rng| {
+        let r = loop {
+            let x: f32 = rng.gen_range(f32::EPSILON, 1.0);
+            let r = a / (x.powf(-2.0 / 3.0) - 1.0).sqrt();
+            if r < 10.0 * a { //cut off the handful of bodies that would start far away
+                break r;
+            }
+        };
+        projected_direction(rng) * r
+    });
+
+    let mut bodies: Vec<Body> = radii.iter()
+        .map(|offset| {
+            //the 3D radius isn't kept, so use the projected one, the rescale below fixes up the total
+            let escape = (2.0 * g * params.mass / a).sqrt() * (1.0 + offset.norm_squared() / (a * a)).powf(-0.25);
+            let q = loop { //von Neumann rejection on q^2 (1 - q^2)^3.5
+                let q: f32 = rng.gen_range(0.0, 1.0);
+                if rng.gen_range(0.0, 0.1) < q * q * (1.0 - q * q).powf(3.5) {
+                    break q;
+                }
+            };
+
+            Body::new(center + offset, body_mass, 0.0, params.body_radius, projected_direction(&mut rng) * q * escape)
+        })
+        .collect();
+
+    virialize(&mut bodies, g);
+    bodies
+}
+
+pub fn cold_collapse(center: Point2, params: &ClusterParams, seed: u64) -> Vec<Body> {
+    //bodies at rest spread evenly through a 3D sphere and projected onto the plane like plummer(),
+    //so the projected density is highest in the middle. It falls in on itself and violently relaxes
+    let mut rng = StdRng::seed_from_u64(seed);
+    let body_mass = params.mass / params.bodies as f32;
+
+    spaced_offsets(&mut rng, params.bodies, params.body_radius * 4.0, |rng| {
+        projected_direction(rng) * params.scale_radius * rng.gen_range(0.0f32, 1.0).cbrt()
+    })
+        .iter()
+        .map(|offset| Body::new(center + offset, body_mass, 0.0, params.body_radius, Vector2::new(0.0, 0.0)))
+        .collect()
+}
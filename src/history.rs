@@ -0,0 +1,217 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::simulation::Simulation;
+
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Clone)]
+pub struct FrameBody { //what replay shows of a body, trails are rebuilt from earlier frames
+    pub id: u64,
+    pub name: Option<String>,
+    pub pos: Point2,
+    pub velocity: Vector2,
+    pub mass: f32,
+    pub charge: f32,
+    pub radius: f32,
+    pub anchored: bool,
+}
+
+impl FrameBody {
+    fn new(body: &Body) -> Self {
+        FrameBody {
+            id: body.id,
+            name: body.name.clone(),
+            pos: body.pos,
+            velocity: body.velocity,
+            mass: body.mass,
+            charge: body.charge,
+            radius: body.radius,
+            anchored: body.anchored,
+        }
+    }
+
+    fn to_body(&self, trail: VecDeque<Point2>) -> Body {
+        Body {
+            id: self.id,
+            name: self.name.clone(),
+            parents: None,
+            pos: self.pos,
+            mass: self.mass,
+            charge: self.charge,
+            radius: self.radius,
+            velocity: self.velocity,
+            trail_length: trail.len(),
+            trail,
+            past_accel: Vector2::new(0.0, 0.0),
+            current_accel: Vector2::new(0.0, 0.0),
+            collision: None,
+            anchored: self.anchored,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Frame {
+    pub time: f32,
+    pub bodies: Vec<FrameBody>,
+}
+
+impl Frame {
+    fn bytes(&self) -> usize { //roughly what the frame takes up in memory
+        mem::size_of::<Frame>()
+            + self.bodies.capacity() * mem::size_of::<FrameBody>()
+            + self.bodies.iter().map(|body| body.name.as_ref().map_or(0, String::capacity)).sum::<usize>()
+    }
+}
+
+#[derive(Clone)]
+pub struct History { //ring buffer of past world states, oldest frames are dropped once it's full
+    frames: VecDeque<Frame>,
+    capacity: usize, //frames
+    max_bytes: usize, //big worlds keep fewer frames
+    bytes: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize, max_bytes: usize) -> Self {
+        History {
+            frames: VecDeque::new(),
+            capacity,
+            max_bytes,
+            bytes: 0,
+        }
+    }
+
+    pub fn push(&mut self, sim: &Simulation) {
+        let frame = Frame {
+            time: sim.time,
+            bodies: sim.bodies.iter().map(FrameBody::new).collect(),
+        };
+        let bytes = frame.bytes();
+
+        if self.capacity == 0 || bytes > self.max_bytes {
+            return;
+        }
+
+        while self.frames.len() >= self.capacity || self.bytes + bytes > self.max_bytes {
+            match self.frames.pop_front() {
+                Some(oldest) => self.bytes -= oldest.bytes(),
+                None => break,
+            }
+        }
+
+        self.bytes += bytes;
+        self.frames.push_back(frame);
+    }
+
+    pub fn get(&self, i: usize) -> Option<&Frame> {
+        self.frames.get(i)
+    }
+
+    pub fn bodies(&self, i: usize, trail_length: usize) -> Vec<Body> { //frame i as bodies, with trails from the frames leading up to it
+        let frame = match self.frames.get(i) {
+            Some(frame) => frame,
+            None => return Vec::new(),
+        };
+
+        let index: HashMap<u64, usize> = frame.bodies.iter().enumerate().map(|(j, body)| (body.id, j)).collect();
+        let mut trails = vec![VecDeque::new(); frame.bodies.len()];
+
+        for earlier in self.frames.range((i + 1).saturating_sub(trail_length)..=i) {
+            for body in earlier.bodies.iter() {
+                if let Some(j) = index.get(&body.id) {
+                    trails[*j].push_back(body.pos);
+                }
+            }
+        }
+
+        frame.bodies.iter()
+            .zip(trails)
+            .map(|(body, trail)| body.to_body(trail))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sim() -> Simulation {
+        let bodies = (0..10)
+            .map(|i| Body::new(Point2::new(i as f32 * 100.0, 0.0), 1.0, 0.0, 1.0, Vector2::new(0.0, 1.0)))
+            .collect();
+        Simulation::new(bodies)
+    }
+
+    #[test]
+    fn keeps_the_newest_frames() {
+        let mut sim = sim();
+        let mut history = History::new(5, usize::MAX);
+        for _ in 0..8 {
+            history.push(&sim);
+            sim.step(1);
+        }
+
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.get(0).unwrap().time, 3.0);
+        assert_eq!(history.get(4).unwrap().time, 7.0);
+    }
+
+    #[test]
+    fn stays_under_the_byte_limit() {
+        let mut sim = sim();
+        let mut probe = History::new(1, usize::MAX);
+        probe.push(&sim);
+        let frame_bytes = probe.bytes();
+
+        let mut history = History::new(100, frame_bytes * 3);
+        for _ in 0..10 {
+            history.push(&sim);
+            sim.step(1);
+        }
+
+        assert_eq!(history.len(), 3);
+        assert!(history.bytes() <= frame_bytes * 3);
+    }
+
+    #[test]
+    fn trails_are_rebuilt() {
+        let mut sim = sim();
+        let mut history = History::new(100, usize::MAX);
+        for _ in 0..10 {
+            history.push(&sim);
+            sim.step(1);
+        }
+
+        let bodies = history.bodies(9, 4);
+        assert_eq!(bodies.len(), 10);
+        for (body, frame_body) in bodies.iter().zip(history.get(9).unwrap().bodies.iter()) {
+            assert_eq!(body.id, frame_body.id);
+            assert_eq!(body.pos, frame_body.pos);
+            assert_eq!(body.trail.len(), 4);
+            assert_eq!(body.trail.back(), Some(&frame_body.pos));
+            assert_eq!(body.trail.front(), Some(&history.get(6).unwrap().bodies.iter().find(|b| b.id == body.id).unwrap().pos));
+        }
+    }
+}
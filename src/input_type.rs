@@ -1,4 +1,6 @@
-#[derive(Clone, Copy)]
+use nbodyrs::editor::BodyField;
+
+#[derive(Clone, Copy, PartialEq)]
 pub enum InputVar{
     Density,
     Radius,
@@ -6,4 +8,52 @@ pub enum InputVar{
     FastForward,
     StepSize,
     Charge,
+    PatternRows,
+    PatternColumns,
+    PatternSpacing,
+    PatternJitter,
+    PatternSpeed,
+    PatternDirection,
+    GravityConstant,
+    CoulombConstant,
+    BodyX, //fields of the selected body, edited from the inspector
+    BodyY,
+    BodyVelocityX,
+    BodyVelocityY,
+    BodyMass,
+    BodyRadius,
+    BodyDensity,
+    BodyCharge,
+    BodyName, //Alt+9, typed as text
+    SelectionVelocityX, //added to every selected body
+    SelectionVelocityY,
+    SelectionMassScale,
+    SelectionCharge,
 }
+
+impl InputVar{
+    pub fn body_field(self) -> Option<BodyField>{ //the inspector's fields
+        match self{
+            InputVar::BodyX => Some(BodyField::X),
+            InputVar::BodyY => Some(BodyField::Y),
+            InputVar::BodyVelocityX => Some(BodyField::VelocityX),
+            InputVar::BodyVelocityY => Some(BodyField::VelocityY),
+            InputVar::BodyMass => Some(BodyField::Mass),
+            InputVar::BodyRadius => Some(BodyField::Radius),
+            InputVar::BodyDensity => Some(BodyField::Density),
+            InputVar::BodyCharge => Some(BodyField::Charge),
+            _ => None,
+        }
+    }
+}
+
+pub const INSPECTOR_FIELDS: [InputVar; 8] = [ //Alt+1-8
+    InputVar::BodyX,
+    InputVar::BodyY,
+    InputVar::BodyVelocityX,
+    InputVar::BodyVelocityY,
+    InputVar::BodyMass,
+    InputVar::BodyRadius,
+    InputVar::BodyDensity,
+    InputVar::BodyCharge,
+];
@@ -0,0 +1,24 @@
+pub mod body;
+pub mod editor;
+pub mod field;
+pub mod forecast;
+pub mod frame;
+pub mod generators;
+pub mod history;
+pub mod orbit;
+pub mod patterns;
+pub mod physics;
+pub mod prediction;
+pub mod recorder;
+pub mod scenarios;
+pub mod scene;
+pub mod selection;
+pub mod simulation;
+pub mod snapshot;
+pub mod stamps;
+pub mod undo;
+pub mod units;
+
+pub use body::Body;
+pub use physics::Integrator;
+pub use simulation::Simulation;
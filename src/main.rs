@@ -2,40 +2,170 @@ extern crate ggez;
 use ggez::*; use ggez::graphics; use ggez::nalgebra as na;
 use ggez::input;
 
-mod body;
-use body::Body;
-
-mod physics;
-use physics::*;
+use nbodyrs::body::Body;
+use nbodyrs::editor::{self, GroupChange};
+use nbodyrs::field::{self, Grid};
+use nbodyrs::forecast::Forecaster;
+use nbodyrs::frame::{co_rotating_path, FrameAxes, RotatingFrame};
+use nbodyrs::history::History;
+use nbodyrs::orbit;
+use nbodyrs::patterns::{self, Pattern, VelocityField};
+use nbodyrs::physics::*;
+use nbodyrs::prediction::Prediction;
+use nbodyrs::recorder::Recorder;
+use nbodyrs::scenarios::{Scenario, SCENARIOS};
+use nbodyrs::scene::{self, Scene};
+use nbodyrs::selection;
+use nbodyrs::simulation::Simulation;
+use nbodyrs::snapshot::{Snapshots, SLOTS};
+use nbodyrs::stamps::Stamps;
+use nbodyrs::undo::{Edit, Params, UndoStack};
+use nbodyrs::units::UnitSystem;
+
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+mod camera;
+use camera::{Camera, Follow};
 
 mod input_type;
 use input_type::*;
 
-use rayon::prelude::*;
+mod replay;
+use replay::Replay;
+
+const HISTORY_LENGTH: usize = 3600; //frames kept for replay
+const HISTORY_BYTES: usize = 256 * 1024 * 1024; //big worlds keep fewer frames
+const FORECAST_DASH: usize = 6; //path points per dash of the forecast ghosts
+const POTENTIAL_CELL: f32 = 10.0; //screen pixels between potential samples
+const CONTOUR_LEVELS: usize = 12;
+const FIELD_CELL: f32 = 40.0; //screen pixels between field arrows
+const STREAMLINE_SEEDS: usize = 12; //per body
+const STREAMLINE_BODIES: usize = 8; //only the most charged or massive bodies get seeds, tracing is expensive
+const STREAMLINE_STEPS: usize = 400;
+const GRAB_DISTANCE: f32 = 8.0; //screen pixels around a velocity arrow's tip that grab it
+const CLICK_DISTANCE: f32 = 4.0; //screen pixels a right drag has to cover to select instead of deleting
+
+const USAGE: &str = "
+usage: nbodyrs [scene.json] [options]
+
+options:
+    --persist-snapshots  keep the snapshot slots in snapshots/ between sessions
+    --record FILE        record trajectories to FILE from startup (.csv for text, anything else for binary)
+    --record-every N     record a frame every N steps (default: 1)
+";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Placement {
+    Free, //drag out the initial velocity
+    Circular,
+    Eccentric,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Grab { //what is being dragged while paused
+    Body{id: u64, offset: Vector2}, //from the cursor to the body's centre
+    Velocity(u64), //the tip of the body's velocity arrow
+}
+
+impl Grab {
+    fn id(self) -> u64 {
+        match self {
+            Grab::Body{id, ..} | Grab::Velocity(id) => id,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Marquee { //right drag selection in view coordinates, so it matches the screen in the co-rotating view
+    Box(Point2, Point2),
+    Lasso(Vec<Point2>),
+}
+
+impl Marquee {
+    fn polygon(&self) -> Vec<Point2> {
+        match self {
+            Marquee::Box(start, end) => selection::rectangle(*start, *end),
+            Marquee::Lasso(points) => points.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Labels {
+    Off,
+    Named, //only bodies with a name
+    All, //unnamed bodies show their id
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PotentialOverlay {
+    Off,
+    Heatmap,
+    Contours, //heatmap with equipotential lines on top
+}
 
-const G: f32 = 6.674;
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum FieldOverlay {
+    Off,
+    Arrows,
+    Streamlines, //seeded around the most charged bodies, or the most massive if none are charged
+}
 
-#[derive(Clone)]
 struct MainState {
-    bodies: Vec<Body>,
+    sim: Simulation,
     start_point: Point2,
     zoom: f32,
-    offset: Point2,
+    offset: Point2, //pan, relative to the camera's target when following one
+    camera: Camera,
     density: f32,
     radius: f32,
     mouse_pos: Point2,
-    trail_length: usize,
     mouse_pressed: bool,
     paused: bool,
-    predict_body: Body,
+    prediction: Option<Prediction>,
     predict_speed: usize,
-    integrator: Integrator,
+    forecaster: Forecaster,
+    show_forecast: bool,
+    forecast_steps: usize,
     help_menu: bool,
+    scenario_menu: bool,
+    scenario: Scenario,
+    pattern_menu: bool,
+    pattern: Pattern,
+    spawn_count: u64, //seeds the jitter so every spawn is different
+    units_menu: bool,
+    units: UnitSystem,
     fast_forward: usize,
-    step_size: f32,
     charge: f32,
     input_type: Option<InputVar>,
     input_buffer: String,
+    scene_path: PathBuf,
+    snapshots: Snapshots,
+    recorder: Option<Recorder>,
+    record_path: PathBuf,
+    record_every: usize,
+    history: History,
+    replay: Option<Replay>,
+    replay_bodies: Option<(usize, Vec<Body>)>, //the replayed frame's index and its bodies, rebuilt when it changes
+    undo_stack: UndoStack,
+    merge_warning: Option<f32>,
+    selected: Option<u64>, //body ids, so they survive the body vector being rebuilt
+    grab: Option<(Grab, Body)>, //and the body before the drag, for undo
+    selection: Vec<u64>, //group picked with a box or lasso
+    marquee: Option<Marquee>,
+    clipboard: Vec<Body>, //from selection::copy_group
+    stamps: Stamps,
+    primary: Option<u64>,
+    placement: Placement,
+    eccentricity: f32,
+    retrograde: bool,
+    potential_overlay: PotentialOverlay,
+    field_overlay: FieldOverlay,
+    labels: Labels,
+    co_rotating: Option<FrameAxes>, //the pair's axes when the co-rotating view was switched on, the pair stays there on screen
 }
 
 type Point2 = na::Point2<f32>;
@@ -44,74 +174,988 @@ type Vector2 = na::Vector2<f32>;
 
 impl MainState {
     fn new() -> Self {
-        let bodies = vec![ //initialize with one massive body in center
-            Body::new(
-                Point2::new(500.0, 400.0), //position
-                300_000.0, //mass
-                0.0, //charge
-                100.0,  //radius
-                Vector2::new(0.0, 0.0)), //velocity
-        ];
-
         MainState {
-            bodies,
+            sim: Scenario::Default.build().sim, //initialize with one massive body in center
             start_point: Point2::new(0.0, 0.0),
             zoom: 1.0,
             offset: Point2::new(0.0, 0.0),
+            camera: Camera::new(),
             density: 0.05,
             radius: 10.0,
             mouse_pos: Point2::new(0.0, 0.0),
-            trail_length: 30,
             mouse_pressed: false,
             paused: false,
-            predict_body: Body::new(Point2::new(0.0, 0.0), 1.0, 0.0, 1.0, Vector2::new(0.0, 0.0)),
+            prediction: None,
             predict_speed: 1,
-            integrator: Integrator::Verlet,
+            forecaster: Forecaster::new(),
+            show_forecast: false,
+            forecast_steps: 300,
             help_menu: false,
+            scenario_menu: false,
+            scenario: Scenario::Default,
+            pattern_menu: false,
+            pattern: Pattern::default(),
+            spawn_count: 0,
+            units_menu: false,
+            units: UnitSystem::Sandbox,
             fast_forward: 1,
-            step_size: 1.0,
             charge: 0.0,
             input_type: None,
             input_buffer: String::new(),
+            scene_path: PathBuf::from("scene.json"),
+            snapshots: Snapshots::new(),
+            recorder: None,
+            record_path: PathBuf::from("trajectory.csv"),
+            record_every: 1,
+            history: History::new(HISTORY_LENGTH, HISTORY_BYTES),
+            replay: None,
+            replay_bodies: None,
+            undo_stack: UndoStack::default(),
+            merge_warning: None,
+            selected: None,
+            grab: None,
+            selection: Vec::new(),
+            marquee: None,
+            clipboard: Vec::new(),
+            stamps: Stamps::load(PathBuf::from("stamps")),
+            primary: None,
+            placement: Placement::Free,
+            eccentricity: 0.5,
+            retrograde: false,
+            potential_overlay: PotentialOverlay::Off,
+            field_overlay: FieldOverlay::Off,
+            labels: Labels::Named,
+            co_rotating: None,
+        }
+    }
+
+    fn view_offset(&self) -> Point2 { //screen position of the world origin, the pan minus the followed target
+        self.offset - self.camera.focus * self.zoom
+    }
+
+    fn to_world(&self, x: f32, y: f32) -> Point2 {
+        let view = self.view_offset();
+        from_view(self.view_axes(), Point2::new((x - view.x) / self.zoom, (y - view.y) / self.zoom))
+    }
+
+    fn view_pair(&self) -> Option<(FrameAxes, &Body, &Body)> { //anchor and pair of the co-rotating view, None in the inertial view
+        let anchor = self.co_rotating?;
+        let (primary, secondary) = self.frame_pair()?;
+        let bodies = self.visible_bodies();
+        Some((anchor, &bodies[primary], &bodies[secondary]))
+    }
+
+    fn view_axes(&self) -> Option<(FrameAxes, FrameAxes)> { //anchor and the pair's axes now
+        let (anchor, primary, secondary) = self.view_pair()?;
+        Some((anchor, FrameAxes::between(primary, secondary)?))
+    }
+
+    fn toggle_co_rotating(&mut self) {
+        self.co_rotating = match self.co_rotating {
+            Some(_) => None,
+            None => {
+                let bodies = self.visible_bodies();
+                let axes = self.frame_pair().and_then(|(primary, secondary)| FrameAxes::between(&bodies[primary], &bodies[secondary]));
+                if axes.is_none() {
+                    println!("The co-rotating frame needs two bodies");
+                }
+                axes
+            },
+        };
+    }
+
+    fn camera_target(&self) -> Option<(Option<u64>, Point2)> { //id of the followed body, None for the barycentre, and its position in the view
+        let bodies = self.visible_bodies();
+        let axes = self.view_axes();
+
+        let target = match self.camera.follow {
+            Follow::Free => None,
+            Follow::Selected => self.selected
+                .and_then(|id| self.find_body(id))
+                .map(|i| (Some(bodies[i].id), bodies[i].pos)),
+            Follow::Barycenter => {
+                let total_mass: f32 = bodies.iter().map(|body| body.mass).sum();
+                if total_mass == 0.0 {
+                    return None;
+                }
+                let weighted = bodies.iter().fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.pos.coords * body.mass);
+                Some((None, Point2::from(weighted / total_mass)))
+            },
+            Follow::Largest => bodies.iter()
+                .max_by(|a, b| a.mass.partial_cmp(&b.mass).unwrap())
+                .map(|body| (Some(body.id), body.pos)),
+        };
+
+        target.map(|(id, pos)| (id, to_view(axes, pos))) //the camera works in the view, which may be co-rotating
+    }
+
+    fn cycle_follow(&mut self, screen: graphics::Rect) {
+        let view = self.view_offset();
+        let previous = self.camera.follow;
+        self.camera.follow = previous.next();
+
+        if self.camera.follow == Follow::Free { //stay where the camera got to
+            self.offset = view;
+            self.camera.focus = Vector2::new(0.0, 0.0);
+        }else if previous == Follow::Free { //pan from the middle of the screen, and glide the target there
+            let center = Point2::new(screen.w / 2.0, screen.h / 2.0);
+            self.camera.focus = (center - view) / self.zoom;
+            self.offset = center;
+        }
+
+        self.camera.retarget();
+    }
+
+    fn launch_velocity(&self, pos: Point2, drag_end: Point2) -> Vector2 { //initial velocity of a body placed at pos
+        let eccentricity = match self.placement {
+            Placement::Free => return self.drag_velocity(pos, drag_end),
+            Placement::Circular => 0.0,
+            Placement::Eccentric => self.eccentricity,
+        };
+
+        orbit::launch_velocity(&self.sim.bodies, pos, self.radius.powi(3) * self.density, eccentricity, self.retrograde, self.sim.forces.g)
+            .unwrap_or_else(|| Vector2::new(0.0, 0.0))
+    }
+
+    fn drag_velocity(&self, pos: Point2, drag_end: Point2) -> Vector2 { //velocity for an arrow dragged out from pos
+        let drag = Vector2::new((drag_end.x - pos.x)/5.0 * self.zoom, (drag_end.y - pos.y)/5.0 * self.zoom);
+        match self.rotating_frame() { //relative to the co-rotating frame, so no drag leaves the body still on screen
+            Some(frame) => frame.velocity_at(pos) + drag,
+            None => drag,
+        }
+    }
+
+    fn velocity_tip(&self, body: &Body, frame: Option<RotatingFrame>) -> Point2 { //where drag_velocity's arrow ends, frame from rotating_frame
+        let relative = match frame {
+            Some(frame) => body.velocity - frame.velocity_at(body.pos),
+            None => body.velocity,
+        };
+        body.pos + relative * 5.0 / self.zoom
+    }
+
+    fn grab_at(&self, pos: Point2, velocity: bool) -> Option<(Grab, Body)> { //velocity arrow tips first, then bodies, velocity grabs a body by its arrow
+        if !self.paused || self.replay.is_some() {
+            return None;
+        }
+
+        let frame = self.rotating_frame();
+        let tip = self.sim.bodies.iter().find(|body| {
+            let tip = self.velocity_tip(body, frame);
+            distance(tip, pos) * self.zoom <= GRAB_DISTANCE && distance(tip, body.pos) > body.radius
+        });
+        if let Some(body) = tip {
+            return Some((Grab::Velocity(body.id), body.clone()));
         }
+
+        let body = &self.sim.bodies[self.sim.body_at(pos)?];
+        let grab = if velocity {Grab::Velocity(body.id)} else {Grab::Body{id: body.id, offset: body.pos - pos}};
+        Some((grab, body.clone()))
+    }
+
+    fn drag_grabbed(&mut self, grab: Grab) {
+        let i = match self.sim.bodies.iter().position(|body| body.id == grab.id()) {
+            Some(i) => i,
+            None => return,
+        };
+
+        match grab {
+            Grab::Body{offset, ..} => editor::move_body(&mut self.sim, i, self.mouse_pos + offset),
+            Grab::Velocity(_) => self.sim.bodies[i].velocity = self.drag_velocity(self.sim.bodies[i].pos, self.mouse_pos),
+        }
+    }
+
+    fn release_grab(&mut self) { //makes the drag undoable
+        if let Some((_, before)) = self.grab.take() {
+            if let Some(after) = self.sim.bodies.iter().find(|body| body.id == before.id) {
+                if after.pos != before.pos || after.velocity != before.velocity {
+                    self.undo_stack.push(Edit::ChangeBodies{before: vec![before], after: vec![after.clone()]});
+                }
+            }
+        }
+    }
+
+    fn predict(&self, world: &Simulation, body: Body) -> Prediction {
+        let mut prediction = Prediction::new(world, body);
+        if let Some((_, primary, secondary)) = self.view_pair() { //to draw the path in the co-rotating view
+            let ids = (primary.id, secondary.id);
+            prediction.track(ids.0);
+            prediction.track(ids.1);
+        }
+        prediction
+    }
+
+    fn predicting(&self) -> bool { //orbit placement previews while hovering, free placement and grabbed bodies while dragging
+        self.mouse_pressed || self.placement != Placement::Free || self.grab.is_some()
+    }
+
+    fn find_body(&self, id: u64) -> Option<usize> {
+        self.visible_bodies().iter().position(|body| body.id == id)
+    }
+
+    fn body_under_mouse(&self) -> Option<usize> {
+        self.visible_bodies().iter().position(|body| distance(self.mouse_pos, body.pos) <= body.radius)
+    }
+
+    fn frame_pair(&self) -> Option<(usize, usize)> { //selected body and its primary, otherwise the two most massive bodies
+        let bodies = self.visible_bodies();
+
+        if let Some(i) = self.selected.and_then(|id| self.find_body(id)) {
+            let primary = self.primary
+                .and_then(|id| self.find_body(id))
+                .filter(|primary| *primary != i)
+                .or_else(|| orbit::dominant_attractor(bodies, i))?;
+            return Some((primary, i));
+        }
+
+        let mut by_mass = (0..bodies.len()).collect::<Vec<usize>>();
+        by_mass.sort_by(|a, b| bodies[*b].mass.partial_cmp(&bodies[*a].mass).unwrap());
+        match by_mass.as_slice() {
+            [primary, secondary, ..] => Some((*primary, *secondary)),
+            _ => None,
+        }
+    }
+
+    fn rotating_frame(&self) -> Option<RotatingFrame> { //None in the inertial frame
+        self.co_rotating?;
+
+        let (primary, secondary) = self.frame_pair()?;
+        let bodies = self.visible_bodies();
+        RotatingFrame::co_rotating(&bodies[primary], &bodies[secondary])
+    }
+
+    fn draw_potential(&self, ctx: &mut Context) { //heatmap and contours sampled on a screen space grid
+        let screen = graphics::screen_coordinates(ctx);
+        let bodies = self.visible_bodies();
+        let mass = self.radius.powi(3) * self.density; //felt by the next placed body
+        let frame = self.rotating_frame();
+        let (offset, zoom, charge, forces, axes) = (self.view_offset(), self.zoom, self.charge, self.sim.forces, self.view_axes()); //the sampling closure runs on other threads
+
+        let grid = Grid::sample(
+            Point2::new(0.0, 0.0),
+            POTENTIAL_CELL,
+            (screen.w / POTENTIAL_CELL) as usize + 2,
+            (screen.h / POTENTIAL_CELL) as usize + 2,
+            |point| {
+                let pos = from_view(axes, Point2::new((point.x - offset.x) / zoom, (point.y - offset.y) / zoom));
+                match &frame {
+                    Some(frame) => field::effective_potential(bodies, pos, mass, charge, forces, frame),
+                    None => field::potential(bodies, pos, mass, charge, forces),
+                }
+            });
+
+        let low = grid.percentile(0.05); //the wells at each body would wash out everything else
+        let high = grid.percentile(0.95);
+        if high <= low { //a flat field has nothing to show
+            return;
+        }
+
+        let mut mesh = graphics::MeshBuilder::new();
+
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let t = ((grid.get(column, row) - low) / (high - low)).clamp(0.0, 1.0);
+                let corner = grid.point(column as f32 - 0.5, row as f32 - 0.5);
+                mesh.rectangle(
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new(corner.x, corner.y, POTENTIAL_CELL, POTENTIAL_CELL),
+                    graphics::Color::new(t, 0.3 * t, 1.0 - t, 0.35)); //deep wells blue, peaks red
+            }
+        }
+
+        if self.potential_overlay == PotentialOverlay::Contours {
+            for level in 1..=CONTOUR_LEVELS {
+                let value = low + (high - low) * level as f32 / (CONTOUR_LEVELS + 1) as f32;
+                for segment in grid.contour(value) {
+                    mesh.line(&segment, 1.0, graphics::Color::new(1.0, 1.0, 1.0, 0.5)).expect("error building contours");
+                }
+            }
+        }
+
+        let heatmap = mesh.build(ctx).expect("error building potential heatmap");
+        graphics::draw(ctx, &heatmap, graphics::DrawParam::new()).expect("error drawing potential heatmap");
+    }
+
+    fn draw_field(&self, ctx: &mut Context) { //acceleration the next placed body would feel, drawn in screen space
+        let screen = graphics::screen_coordinates(ctx);
+        let bodies = self.visible_bodies();
+        let mass = self.radius.powi(3) * self.density;
+        let view = self.view_offset();
+        let axes = self.view_axes();
+        let to_world = |x: f32, y: f32| from_view(axes, Point2::new((x - view.x) / self.zoom, (y - view.y) / self.zoom));
+        let to_screen = |pos: Point2| {
+            let pos = to_view(axes, pos);
+            Point2::new(pos.x * self.zoom + view.x, pos.y * self.zoom + view.y)
+        };
+
+        let mut mesh = graphics::MeshBuilder::new();
+        let mut empty = true;
+
+        match self.field_overlay {
+            FieldOverlay::Off => return,
+
+            FieldOverlay::Arrows => {
+                let columns = (screen.w / FIELD_CELL) as usize;
+                let rows = (screen.h / FIELD_CELL) as usize;
+                let arrows = (0..rows * columns)
+                    .map(|i| {
+                        let tail = Point2::new(((i % columns) as f32 + 0.5) * FIELD_CELL, ((i / columns) as f32 + 0.5) * FIELD_CELL);
+                        (tail, view_direction(axes, field::acceleration(bodies, to_world(tail.x, tail.y), mass, self.charge, self.sim.forces)))
+                    })
+                    .filter(|(_, accel)| accel.norm().is_normal())
+                    .collect::<Vec<(Point2, Vector2)>>();
+
+                //arrow length on a log scale between the weakest and strongest few percent, so far arrows don't vanish
+                let mut magnitudes = arrows.iter().map(|(_, accel)| accel.norm().ln()).collect::<Vec<f32>>();
+                magnitudes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let (low, high) = match magnitudes.len() {
+                    0 => (0.0, 1.0),
+                    n => (magnitudes[n / 20], magnitudes[n - 1 - n / 20]),
+                };
+
+                for (tail, accel) in arrows.iter() {
+                    let t = if high > low {((accel.norm().ln() - low) / (high - low)).clamp(0.0, 1.0)} else {1.0};
+                    let direction = accel / accel.norm();
+                    let head = tail + direction * FIELD_CELL * (0.25 + 0.6 * t);
+                    let side = Vector2::new(-direction.y, direction.x) * 4.0;
+                    let color = graphics::Color::new(0.4 + 0.6 * t, 1.0 - 0.5 * t, 0.4, 0.7);
+
+                    mesh.line(&[*tail, head], 1.5, color).expect("error building field arrow");
+                    mesh.line(&[head - direction * 6.0 + side, head, head - direction * 6.0 - side], 1.5, color).expect("error building field arrow");
+                    empty = false;
+                }
+            },
+
+            FieldOverlay::Streamlines => {
+                let charged = bodies.iter().any(|body| body.charge != 0.0);
+                let weight = |body: &Body| if charged {body.charge.abs()} else {body.mass.abs()};
+                let step = 4.0 / self.zoom; //a few screen pixels per step
+
+                let mut seeded = bodies.iter().filter(|body| weight(body) > 0.0).collect::<Vec<&Body>>();
+                seeded.sort_by(|a, b| weight(b).partial_cmp(&weight(a)).unwrap());
+
+                for body in seeded.into_iter().take(STREAMLINE_BODIES) {
+                    for i in 0..STREAMLINE_SEEDS {
+                        let angle = 2.0 * std::f32::consts::PI * i as f32 / STREAMLINE_SEEDS as f32;
+                        let seed = body.pos + Vector2::new(angle.cos(), angle.sin()) * (body.radius + step);
+
+                        //lines leave each seeding body, against the field if it points back in
+                        let outwards = field::acceleration(bodies, seed, mass, self.charge, self.sim.forces).dot(&(seed - body.pos)) >= 0.0;
+                        let line = field::streamline(bodies, seed, mass, self.charge, self.sim.forces, if outwards {step} else {-step}, STREAMLINE_STEPS)
+                            .into_iter()
+                            .map(to_screen)
+                            .collect::<Vec<Point2>>();
+
+                        if line.len() > 1 {
+                            mesh.line(&line, 1.0, graphics::Color::new(0.4, 1.0, 0.6, 0.5)).expect("error building streamline");
+                            empty = false;
+                        }
+                    }
+                }
+            },
+        }
+
+        if !empty {
+            let field = mesh.build(ctx).expect("error building field overlay");
+            graphics::draw(ctx, &field, graphics::DrawParam::new()).expect("error drawing field overlay");
+        }
+    }
+
+    fn orbit_info(&self) -> Option<String> { //orbital elements of the selected body, or the one under the mouse
+        let bodies = self.visible_bodies();
+        let i = self.selected.and_then(|id| self.find_body(id)).or_else(|| self.body_under_mouse())?;
+        let primary = self.primary
+            .and_then(|id| self.find_body(id))
+            .filter(|primary| *primary != i)
+            .or_else(|| orbit::dominant_attractor(bodies, i))?;
+
+        let elements = orbit::orbital_elements(&bodies[i], &bodies[primary], self.sim.forces.g)?;
+
+        Some(format!(
+            "
+            Orbit of body {id} around body {primary_id}
+            Semi-major axis: {a} {length}
+            Eccentricity: {e}
+            Argument of periapsis: {w:.1}°
+            Period: {period}
+            True anomaly: {nu:.1}°
+            ",
+            id = bodies[i].id,
+            primary_id = bodies[primary].id,
+            a = elements.semi_major_axis,
+            length = self.units.length(),
+            e = elements.eccentricity,
+            w = elements.argument_of_periapsis.to_degrees(),
+            period = elements.period.map_or("unbound".to_string(), |period| format!("{} {}", period, self.units.time())),
+            nu = elements.true_anomaly.to_degrees()))
+    }
+
+    fn inspector(&self) -> Option<String> { //properties of the selected body, the field being edited shows the input instead
+        let bodies = self.visible_bodies();
+        let body = &bodies[self.selected.and_then(|id| self.find_body(id))?];
+        let accel = acceleration_at(bodies, body.pos, body.mass, body.charge, body.radius, self.sim.forces);
+        let units = &self.units;
+
+        let values = [
+            (body.pos.x, units.length().to_string()),
+            (body.pos.y, units.length().to_string()),
+            (body.velocity.x, units.velocity()),
+            (body.velocity.y, units.velocity()),
+            (body.mass, units.mass().to_string()),
+            (body.radius, units.length().to_string()),
+            (body.mass / body.radius.powi(3), units.density()),
+            (body.charge, units.charge().to_string()),
+        ];
+        let names = ["x", "y", "Velocity x", "Velocity y", "Mass", "Radius", "Density", "Charge"];
+
+        let fields = INSPECTOR_FIELDS.iter()
+            .zip(values.iter().zip(names.iter()))
+            .enumerate()
+            .map(|(i, (field, ((value, unit), name)))| match self.input_type {
+                Some(input) if input == *field => format!("            Alt+{} - {}: {}_", i + 1, name, self.input_buffer.chars().skip(1).collect::<String>()),
+                _ => format!("            Alt+{} - {}: {} {}", i + 1, name, value, unit),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let name = match self.input_type {
+            Some(InputVar::BodyName) => format!("{}_", self.input_buffer.chars().skip(1).collect::<String>()),
+            _ => body.name.clone().unwrap_or_else(|| "none".to_string()),
+        };
+
+        Some(format!(
+            "
+            Body {id} (Escape to deselect){parents}
+{fields}
+            Alt+9 - Name: {name}
+            Speed: {speed} {velocity}
+            Acceleration: {ax}, {ay} ({accel} {length}/{time}^2)
+            ",
+            id = body.id,
+            parents = body.parents.map_or(String::new(), |(a, b)| format!("\n            Merged from bodies {} and {}", a, b)),
+            fields = fields,
+            name = name,
+            speed = body.velocity.norm(),
+            velocity = units.velocity(),
+            ax = accel.x,
+            ay = accel.y,
+            accel = accel.norm(),
+            length = units.length(),
+            time = units.time()))
+    }
+
+    fn inspect(&mut self, field: usize) { //start editing one of the inspector's fields
+        if self.replay.is_some() {
+            println!("Bodies can't be edited during replay");
+        }else if self.selected.and_then(|id| self.find_body(id)).is_none() {
+            println!("Click a body to inspect it first");
+        }else if let Some(var) = INSPECTOR_FIELDS.get(field).cloned().or(Some(InputVar::BodyName).filter(|_| field == INSPECTOR_FIELDS.len())) { //Alt+9 names the body
+            self.begin_input(var);
+        }
+    }
+
+    fn begin_input(&mut self, var: InputVar) { //for inputs opened by keys that don't type a character
+        self.input_type = Some(var);
+        self.input_buffer = String::from(" "); //stands in for the key that starts the other inputs
+    }
+
+    fn edit_selected<F: Fn(&Body) -> Result<Body, &'static str>>(&mut self, change: F) { //renames and inspector edits
+        let before = match self.selected.and_then(|id| self.sim.bodies.iter().find(|body| body.id == id)) {
+            Some(body) => body.clone(),
+            None => {
+                println!("The selected body is gone");
+                return;
+            },
+        };
+
+        match change(&before) {
+            Ok(after) => self.edit(Edit::ChangeBodies{before: vec![before], after: vec![after]}),
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    fn selected_bodies(&self) -> Vec<Body> {
+        editor::find_bodies(&self.sim, &self.selection)
+    }
+
+    fn change_selection(&mut self, change: GroupChange) { //one undoable edit for the whole group
+        match editor::change_group(&self.sim, &self.selection, change) {
+            Ok(edit) => self.edit(edit),
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    fn finish_marquee(&mut self, marquee: Marquee, at: Point2) { //select what's inside, a right click without dragging deletes as before
+        let polygon = marquee.polygon();
+        let size = polygon.iter().map(|point| distance(*point, polygon[0])).fold(0.0, f32::max) * self.zoom;
+
+        if size < CLICK_DISTANCE {
+            println!("Removing body at {} {}", at.x, at.y);
+            let removed = self.sim.remove_bodies_at(at); //delete any bodies under mouse
+            if !removed.is_empty() {
+                self.undo_stack.push(Edit::RemoveBodies(removed));
+            }
+            return;
+        }
+
+        let axes = self.view_axes();
+        self.selection = self.sim.bodies.iter()
+            .filter(|body| selection::in_polygon(to_view(axes, body.pos), &polygon))
+            .map(|body| body.id)
+            .collect();
+    }
+
+    fn selection_key(&mut self, keycode: input::keyboard::KeyCode, keymods: input::keyboard::KeyMods) -> bool { //returns true if the selection dialog used the key
+        if keymods.contains(input::keyboard::KeyMods::CTRL) {
+            return false;
+        }
+
+        if keymods.contains(input::keyboard::KeyMods::ALT) && keymods.contains(input::keyboard::KeyMods::SHIFT) {
+            match number_key(keycode) {
+                Some(slot) => self.save_stamp(slot),
+                None => return false,
+            }
+            return true;
+        }
+
+        if keymods.contains(input::keyboard::KeyMods::ALT) || keymods.contains(input::keyboard::KeyMods::SHIFT) { //Shift+digits still restore snapshots
+            return false;
+        }
+
+        match keycode {
+            input::keyboard::KeyCode::F1 | input::keyboard::KeyCode::Delete => {
+                let bodies = self.selected_bodies();
+                self.edit(Edit::RemoveBodies(bodies));
+                self.selection.clear();
+            },
+            input::keyboard::KeyCode::F2 => self.change_selection(GroupChange::ZeroVelocity),
+            input::keyboard::KeyCode::F3 => self.begin_input(InputVar::SelectionVelocityX),
+            input::keyboard::KeyCode::F4 => self.begin_input(InputVar::SelectionVelocityY),
+            input::keyboard::KeyCode::F5 => self.begin_input(InputVar::SelectionMassScale),
+            input::keyboard::KeyCode::F6 => self.begin_input(InputVar::SelectionCharge),
+            input::keyboard::KeyCode::F7 => self.change_selection(GroupChange::ToggleAnchor),
+            input::keyboard::KeyCode::F8 => self.copy_selection(),
+            input::keyboard::KeyCode::Escape => self.selection.clear(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn copy_selection(&mut self) {
+        self.clipboard = selection::copy_group(&self.selected_bodies());
+        println!("Copied {} bodies", self.clipboard.len());
+    }
+
+    fn paste(&mut self, group: &[Body]) { //at the cursor
+        if group.is_empty() {
+            println!("Nothing to paste");
+            return;
+        }
+
+        let bodies = selection::paste_group(group, self.mouse_pos);
+        self.selection = bodies.iter().map(|body| body.id).collect();
+        self.edit(Edit::AddBodies(bodies));
+    }
+
+    fn save_stamp(&mut self, slot: usize) {
+        match self.stamps.save(slot, selection::copy_group(&self.selected_bodies())) {
+            Ok(()) => println!("Saved stamp {}", slot + 1),
+            Err(e) => println!("Error saving stamp {}: {}", slot + 1, e),
+        }
+    }
+
+    fn paste_stamp(&mut self, slot: usize) {
+        match self.stamps.get(slot).map(|stamp| stamp.to_vec()) {
+            Some(stamp) => self.paste(&stamp),
+            None => println!("Stamp {} is empty", slot + 1),
+        }
+    }
+
+    fn params(&self) -> Params {
+        Params {
+            density: self.density,
+            radius: self.radius,
+            charge: self.charge,
+            trail_length: self.sim.trail_length,
+            predict_speed: self.predict_speed,
+            forecast_steps: self.forecast_steps,
+            fast_forward: self.fast_forward,
+            step_size: self.sim.step_size.abs(), //the direction is left to Simulation::reverse(), so undo never flips it
+            integrator: self.sim.integrator,
+            pattern: self.pattern,
+            units: self.units,
+            forces: self.sim.forces,
+        }
+    }
+
+    fn set_step_size(&mut self, step_size: f32) { //a sign change goes through reverse(), which fixes up Verlet's velocities and the merge times
+        if (step_size < 0.0) != self.sim.is_reversed() {
+            self.sim.reverse();
+        }
+        self.sim.step_size = step_size;
+    }
+
+    fn set_params(&mut self, params: Params) {
+        self.density = params.density;
+        self.radius = params.radius;
+        self.charge = params.charge;
+        self.sim.trail_length = params.trail_length;
+        self.predict_speed = params.predict_speed;
+        self.forecast_steps = params.forecast_steps;
+        self.fast_forward = params.fast_forward;
+        self.sim.step_size = params.step_size.copysign(self.sim.step_size);
+        self.sim.integrator = params.integrator;
+        self.pattern = params.pattern;
+        self.units = params.units;
+        self.sim.forces = params.forces;
+    }
+
+    fn apply_edit(&mut self, edit: Edit) {
+        match edit {
+            Edit::Params{after, ..} => self.set_params(after),
+            edit => editor::apply(&mut self.sim, &edit),
+        }
+    }
+
+    fn edit(&mut self, edit: Edit) { //apply an editing action and make it undoable
+        self.undo_stack.push(edit.clone());
+        self.apply_edit(edit);
+    }
+
+    fn undo(&mut self) {
+        match self.undo_stack.undo() {
+            Some(edit) => self.apply_edit(edit),
+            None => println!("Nothing to undo"),
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.undo_stack.redo() {
+            Some(edit) => self.apply_edit(edit),
+            None => println!("Nothing to redo"),
+        }
+    }
+
+    fn step(&mut self) {
+        for _i in 0..self.fast_forward { //step one at a time so history and the recorder see every step
+            let count = self.sim.bodies.len();
+            self.sim.step(1);
+            self.history.push(&self.sim);
+
+            if self.sim.bodies.len() < count {
+                self.follow_merges();
+            }
+
+            if let Some(recorder) = &mut self.recorder {
+                if let Err(e) = recorder.record(&self.sim) {
+                    println!("Error recording to {}: {}", recorder.path().display(), e);
+                    self.recorder = None;
+                }
+            }
+        }
+    }
+
+    fn follow_merges(&mut self) { //picked bodies that just merged hand over to the merged body, so the selection and camera keep tracking it
+        let merged_into = editor::merged_into(&self.sim);
+
+        self.selected = self.selected.map(|id| editor::follow(&merged_into, id));
+        self.primary = self.primary.map(|id| editor::follow(&merged_into, id));
+        self.selection = editor::follow_all(&merged_into, &self.selection);
+    }
+
+    fn toggle_replay(&mut self) {
+        if self.replay.is_some() {
+            self.replay = None;
+            self.replay_bodies = None;
+        }else if self.history.is_empty() {
+            println!("Nothing to replay yet");
+        }else {
+            if self.grab.is_some() {
+                self.release_grab();
+            }
+            self.replay = Some(Replay::new());
+            self.mouse_pressed = false;
+            self.marquee = None; //the live bodies it would select aren't on screen
+            self.input_type = None;
+            self.input_buffer = String::new();
+            self.show_replay_frame();
+        }
+    }
+
+    fn show_replay_frame(&mut self) { //rebuilds the replayed bodies if the frame changed
+        let index = match &self.replay {
+            Some(replay) => replay.index(),
+            None => return,
+        };
+
+        if self.replay_bodies.as_ref().map(|(shown, _)| *shown) != Some(index) {
+            self.replay_bodies = Some((index, self.history.bodies(index, self.sim.trail_length)));
+        }
+    }
+
+    fn replay_key(&mut self, keycode: input::keyboard::KeyCode) -> bool { //returns true if the key was used by the replay controls
+        let frames = self.history.len();
+
+        match (&mut self.replay, keycode) {
+            (Some(replay), input::keyboard::KeyCode::Space) => replay.toggle_playing(frames),
+            (Some(replay), input::keyboard::KeyCode::Key1) => replay.slower(),
+            (Some(replay), input::keyboard::KeyCode::Key2) => replay.faster(),
+            _ => return false,
+        };
+
+        true
+    }
+
+    fn visible_bodies(&self) -> &[Body] { //the replayed frame during replay, otherwise the live simulation
+        match (&self.replay, &self.replay_bodies) {
+            (Some(_), Some((_, bodies))) => bodies,
+            _ => &self.sim.bodies,
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        match self.recorder.take() {
+            None => match Recorder::create(&self.record_path, self.record_every) {
+                Ok(mut recorder) => match recorder.record(&self.sim) { //starts at the current state, like the headless runner
+                    Ok(()) => {
+                        println!("Recording to {}", self.record_path.display());
+                        self.recorder = Some(recorder);
+                    },
+                    Err(e) => println!("Error recording to {}: {}", self.record_path.display(), e),
+                },
+                Err(e) => println!("Error recording to {}: {}", self.record_path.display(), e),
+            },
+
+            Some(recorder) => {
+                let frames = recorder.frames();
+                match recorder.finish() {
+                    Ok(()) => println!("Recorded {} frames to {}", frames, self.record_path.display()),
+                    Err(e) => println!("Error recording to {}: {}", self.record_path.display(), e),
+                }
+            },
+        }
+    }
+
+    fn to_scene(&self) -> Scene {
+        Scene {
+            sim: self.sim.clone(),
+            density: self.density,
+            charge: self.charge,
+            offset: self.view_offset(),
+            zoom: self.zoom,
+            units: self.units,
+        }
+    }
+
+    fn apply_scene(&mut self, scene: Scene) {
+        self.history.clear();
+        self.undo_stack.clear();
+        self.replay = None;
+        self.sim = scene.sim;
+        self.density = scene.density;
+        self.charge = scene.charge;
+        self.offset = scene.offset;
+        self.camera = Camera::new();
+        self.co_rotating = None;
+        self.zoom = scene.zoom;
+        self.units = scene.units;
+    }
+
+    fn spawn_pattern(&mut self) { //at the cursor, around the chosen primary if there is one
+        let limited = self.pattern.limited();
+        if limited != self.pattern {
+            println!("Patterns are limited to {} bodies, spawning {} rows and {} columns ({} bodies)", patterns::MAX_BODIES, limited.rows, limited.columns, limited.count());
+        }
+
+        let template = Body::new(self.mouse_pos, self.radius.powi(3) * self.density, self.charge, self.radius, Vector2::new(0.0, 0.0));
+        let bodies = editor::spawn_pattern(&self.sim, &self.pattern, &template, self.primary, self.spawn_count);
+
+        self.spawn_count += 1;
+        self.edit(Edit::AddBodies(bodies));
+    }
+
+    fn pattern_key(&mut self, keycode: input::keyboard::KeyCode) { //keys while the pattern dialog is open
+        match keycode {
+            input::keyboard::KeyCode::Key1 => self.pattern.shape = self.pattern.shape.next(),
+            input::keyboard::KeyCode::Key2 => self.input_type = Some(InputVar::PatternRows),
+            input::keyboard::KeyCode::Key3 => self.input_type = Some(InputVar::PatternColumns),
+            input::keyboard::KeyCode::Key4 => self.input_type = Some(InputVar::PatternSpacing),
+            input::keyboard::KeyCode::Key5 => self.input_type = Some(InputVar::PatternJitter),
+            input::keyboard::KeyCode::Key6 => self.pattern.velocity = self.pattern.velocity.next(),
+            input::keyboard::KeyCode::Key7 => self.input_type = Some(InputVar::PatternSpeed),
+            input::keyboard::KeyCode::Key8 => self.input_type = Some(InputVar::PatternDirection),
+            input::keyboard::KeyCode::G => {
+                self.spawn_pattern();
+                self.pattern_menu = false;
+            },
+            input::keyboard::KeyCode::N | input::keyboard::KeyCode::Escape => self.pattern_menu = false,
+            _ => {},
+        }
+    }
+
+    fn units_key(&mut self, keycode: input::keyboard::KeyCode) { //keys while the units dialog is open
+        match keycode {
+            input::keyboard::KeyCode::Key1 => {
+                self.units = self.units.next();
+                self.sim.forces = self.units.forces();
+            },
+            input::keyboard::KeyCode::Key2 => self.input_type = Some(InputVar::GravityConstant),
+            input::keyboard::KeyCode::Key3 => self.input_type = Some(InputVar::CoulombConstant),
+            input::keyboard::KeyCode::K | input::keyboard::KeyCode::Escape => self.units_menu = false,
+            _ => {},
+        }
+    }
+
+    fn load_scenario(&mut self, scenario: Scenario) { //unlike apply_scene this keeps the placement settings
+        let scene = scenario.build();
+
+        self.history.clear();
+        self.undo_stack.clear();
+        self.replay = None;
+        self.sim = scene.sim;
+        self.offset = scene.offset;
+        self.camera = Camera::new();
+        self.co_rotating = None;
+        self.zoom = scene.zoom;
+        self.units = scene.units;
+        self.scenario = scenario;
+    }
+
+    fn save_scene(&self) {
+        match scene::save(&self.scene_path, &self.to_scene()) {
+            Ok(()) => println!("Saved scene to {}", self.scene_path.display()),
+            Err(e) => println!("Error saving scene to {}: {}", self.scene_path.display(), e),
+        }
+    }
+
+    fn load_scene(&mut self) {
+        match scene::load(&self.scene_path) {
+            Ok(scene) => {
+                self.apply_scene(scene);
+                println!("Loaded scene from {}", self.scene_path.display());
+            },
+            Err(e) => println!("Error loading scene from {}: {}", self.scene_path.display(), e),
+        }
+    }
+
+    fn save_snapshot(&mut self, slot: usize) {
+        match self.snapshots.save(slot, &self.sim) {
+            Ok(()) => println!("Saved snapshot {}", slot + 1),
+            Err(e) => println!("Error saving snapshot {}: {}", slot + 1, e),
+        }
+    }
+
+    fn restore_snapshot(&mut self, slot: usize) {
+        match self.snapshots.restore(slot) {
+            Some(sim) => {
+                self.history.clear();
+                self.undo_stack.clear();
+                self.replay = None;
+                self.sim = sim;
+                println!("Restored snapshot {}", slot + 1);
+            },
+            None => println!("Snapshot {} is empty", slot + 1),
+        }
+    }
+}
+
+fn to_view(axes: Option<(FrameAxes, FrameAxes)>, pos: Point2) -> Point2 { //world to co-rotating view, from view_axes
+    match axes {
+        Some((anchor, now)) => anchor.from_frame(now.to_frame(pos)),
+        None => pos,
+    }
+}
+
+fn from_view(axes: Option<(FrameAxes, FrameAxes)>, pos: Point2) -> Point2 {
+    match axes {
+        Some((anchor, now)) => now.from_frame(anchor.to_frame(pos)),
+        None => pos,
     }
 }
 
+fn view_direction(axes: Option<(FrameAxes, FrameAxes)>, v: Vector2) -> Vector2 {
+    match axes {
+        Some((anchor, now)) => anchor.rotate_out(now.rotate_in(v)),
+        None => v,
+    }
+}
+
+fn number_key(keycode: input::keyboard::KeyCode) -> Option<usize> { //number keys 1-9 map to 0-8, for snapshot slots and menus
+    match keycode {
+        input::keyboard::KeyCode::Key1 => Some(0),
+        input::keyboard::KeyCode::Key2 => Some(1),
+        input::keyboard::KeyCode::Key3 => Some(2),
+        input::keyboard::KeyCode::Key4 => Some(3),
+        input::keyboard::KeyCode::Key5 => Some(4),
+        input::keyboard::KeyCode::Key6 => Some(5),
+        input::keyboard::KeyCode::Key7 => Some(6),
+        input::keyboard::KeyCode::Key8 => Some(7),
+        input::keyboard::KeyCode::Key9 => Some(8),
+        _ => None,
+    }
+}
+
+fn pattern_count(num: f32) -> usize { //rows or columns, clamped so a typo like 1e9 can't spawn billions of bodies
+    let count = num.max(0.0) as usize;
+    if count > patterns::MAX_COUNT {
+        println!("Patterns can have at most {} rows or columns, using {}", patterns::MAX_COUNT, patterns::MAX_COUNT);
+    }
+    count.min(patterns::MAX_COUNT)
+}
+
+fn replay_allows(keycode: input::keyboard::KeyCode) -> bool { //keys that only move the view, open help or leave the replay
+    matches!(keycode,
+        input::keyboard::KeyCode::P
+        | input::keyboard::KeyCode::H
+        | input::keyboard::KeyCode::Up
+        | input::keyboard::KeyCode::Down
+        | input::keyboard::KeyCode::Left
+        | input::keyboard::KeyCode::Right
+        | input::keyboard::KeyCode::Tab
+        | input::keyboard::KeyCode::L
+        | input::keyboard::KeyCode::C
+        | input::keyboard::KeyCode::J
+        | input::keyboard::KeyCode::F
+        | input::keyboard::KeyCode::Slash)
+}
 
 impl event::EventHandler for MainState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let target = self.camera_target();
+        self.camera.update(target.and_then(|(id, _)| id), target.map(|(_, pos)| pos));
+
         let mouse_pos = input::mouse::position(ctx);
-        self.mouse_pos.x = (mouse_pos.x - self.offset.x)/self.zoom;
-        self.mouse_pos.y = (mouse_pos.y - self.offset.y)/self.zoom;
+        self.mouse_pos = self.to_world(mouse_pos.x, mouse_pos.y);
 
-        if !self.paused{ //physics sim
-            (0..self.fast_forward).for_each(|_i|{
-                self.bodies = update_velocities_and_collide(&self.bodies, self.integrator, self.step_size);
+        if !self.selection.is_empty() { //deleted bodies drop out, merged ones were already swapped for the merged body in step()
+            let ids = self.sim.bodies.iter().map(|body| body.id).collect::<HashSet<u64>>();
+            self.selection.retain(|id| ids.contains(id));
+        }
 
-                (0..self.bodies.len()).for_each(|i|{
-                    self.bodies[i].trail_length = self.trail_length;
-                })
-            })
+        if let Some(replay) = &mut self.replay { //physics stays off during replay
+            replay.update(self.history.len());
+            self.show_replay_frame();
+            return Ok(());
         }
 
-        //simulate prediction
-        if self.mouse_pressed{
-            for _i in 0..self.predict_speed { //reimplementation of update_bodies_and_collide() but for only predict body
-                self.predict_body.current_accel = self.bodies.iter()
-                    .fold(Vector2::new(0.0, 0.0), |acc: Vector2, body|{
-                        let r = distance(body.pos, self.predict_body.pos);
-                        let a_mag = (G*body.mass)/(r.powi(2));
-                        let angle = angle(body.pos, self.predict_body.pos);
-                        acc + Vector2::new(a_mag * angle.cos(), a_mag * angle.sin())
-                    });
+        if !self.paused{ //physics sim
+            self.step();
+        }
 
-                self.predict_body.trail_length += 1; //infinite trail length
-                self.predict_body.update_trail();
+        let merge = self.sim.irreversible_merge();
+        if merge.is_some() && merge != self.merge_warning {
+            println!("Warning: bodies merged at t = {}, running backwards past it won't retrace the forward run", merge.unwrap_or(0.0));
+        }
+        self.merge_warning = merge;
 
-                match self.integrator{
-                    Integrator::Euler => self.predict_body.update_euler(self.step_size),
-                    Integrator::Verlet => self.predict_body.update_verlet(self.step_size),
-                };
+        if self.show_forecast {
+            self.forecaster.refresh(&self.sim, self.forecast_steps);
+        }
+
+        //simulate prediction
+        if self.predicting(){
+            if let Some(prediction) = &mut self.prediction {
+                prediction.advance(self.predict_speed);
             }
         }
 
@@ -120,7 +1164,16 @@ impl event::EventHandler for MainState {
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         graphics::clear(ctx, graphics::Color::new(0.0, 0.0, 0.0, 0.01));
-        if !self.help_menu {
+        if self.scenario_menu {
+            let menu = SCENARIOS.iter()
+                .enumerate()
+                .map(|(i, scenario)| format!("                    {} - {}{}", i + 1, scenario.name(), if *scenario == self.scenario {" (current)"} else {""}))
+                .collect::<Vec<String>>()
+                .join("\n\n");
+
+            let text = graphics::Text::new(format!("\n                    Pick a scenario, M to close\n\n{}", menu));
+            graphics::draw(ctx, &text, graphics::DrawParam::new()).expect("error drawing scenario menu");
+        }else if !self.help_menu {
             {
                 let input_display = match self.input_type{
                     None => "None",
@@ -130,53 +1183,274 @@ impl event::EventHandler for MainState {
                     Some(InputVar::StepSize) => "Step Size",
                     Some(InputVar::FastForward) => "Sim Speed",
                     Some(InputVar::Charge) => "Charge",
+                    Some(InputVar::PatternRows) => "Pattern Rows",
+                    Some(InputVar::PatternColumns) => "Pattern Columns",
+                    Some(InputVar::PatternSpacing) => "Pattern Spacing",
+                    Some(InputVar::PatternJitter) => "Pattern Jitter",
+                    Some(InputVar::PatternSpeed) => "Pattern Speed",
+                    Some(InputVar::PatternDirection) => "Pattern Direction",
+                    Some(InputVar::GravityConstant) => "G",
+                    Some(InputVar::CoulombConstant) => "k",
+                    Some(InputVar::BodyX) => "Body x",
+                    Some(InputVar::BodyY) => "Body y",
+                    Some(InputVar::BodyVelocityX) => "Body Velocity x",
+                    Some(InputVar::BodyVelocityY) => "Body Velocity y",
+                    Some(InputVar::BodyMass) => "Body Mass",
+                    Some(InputVar::BodyRadius) => "Body Radius",
+                    Some(InputVar::BodyDensity) => "Body Density",
+                    Some(InputVar::BodyCharge) => "Body Charge",
+                    Some(InputVar::BodyName) => "Body Name",
+                    Some(InputVar::SelectionVelocityX) => "Selection Velocity x",
+                    Some(InputVar::SelectionVelocityY) => "Selection Velocity y",
+                    Some(InputVar::SelectionMassScale) => "Selection Mass Scale",
+                    Some(InputVar::SelectionCharge) => "Selection Charge",
                 };
 
                 //top left ui text
                 let info = format!(
                     "
-                    Offset: {x}, {y}
-                    Zoom: {zoom}
-                    Density: {density}
-                    Charge: {charge}
-                    Radius: {radius}
-                    Trail length: {trail_length}
-                    Prediction Speed: {prediction_speed}
-                    Integrator: {method}
-                    Sim Speed: {sim_speed}
-                    Step Size: {step_size}
+                    Offset: {x}, {y} px
+                    Zoom: {zoom}x
+                    Camera: {camera}
+                    Density: {density} {density_unit}
+                    Charge: {charge} {charge_unit}
+                    Radius: {radius} {length}
+                    Trail length: {trail_length} steps
+                    Prediction Speed: {prediction_speed} steps/frame
+                    Forecast: {forecast}
+                    Potential: {potential}
+                    Field: {field}
+                    Frame: {frame}
+                    Labels: {labels}
+                    Integrator: {method:?}
+                    Placement: {placement}
+                    Sim Speed: {sim_speed} steps/frame
+                    Step Size: {step_size} {time_unit}
+                    Time: {time} {time_unit}{reversed}
+                    Units: {units} (G = {g}, k = {k})
                     Inputting: {inputtype} - {inbuffer}
+                    Snapshots: {snapshots}
+                    Recording: {recording}
+                    Replay: {replay}
                     Press H for keybinds
                     ",
                     x = self.offset.x,
                     y = self.offset.y, 
                     zoom = self.zoom,
+                    camera = match (self.camera.follow, self.camera_target()) {
+                        (Follow::Free, _) => "free".to_string(),
+                        (Follow::Selected, Some((Some(id), _))) => format!("following body {}", id),
+                        (Follow::Selected, _) => "following the selected body (none selected)".to_string(),
+                        (Follow::Barycenter, _) => "following the barycentre".to_string(),
+                        (Follow::Largest, Some((Some(id), _))) => format!("following the most massive body ({})", id),
+                        (Follow::Largest, _) => "following the most massive body".to_string(),
+                    },
                     density = self.density,
+                    density_unit = self.units.density(),
                     charge = self.charge,
+                    charge_unit = self.units.charge(),
                     radius = self.radius,
-                    trail_length = self.trail_length,
+                    length = self.units.length(),
+                    trail_length = self.sim.trail_length,
                     prediction_speed = self.predict_speed,
-                    method = format!("{:?}", self.integrator),
+                    potential = match self.potential_overlay {
+                        PotentialOverlay::Off => "off",
+                        PotentialOverlay::Heatmap => "heatmap",
+                        PotentialOverlay::Contours => "heatmap and contours",
+                    },
+                    field = match self.field_overlay {
+                        FieldOverlay::Off => "off",
+                        FieldOverlay::Arrows => "arrows",
+                        FieldOverlay::Streamlines => "streamlines",
+                    },
+                    frame = match self.frame_pair() {
+                        Some((primary, secondary)) if self.co_rotating.is_some() => format!(
+                            "co-rotating with bodies {} and {}",
+                            self.visible_bodies()[primary].id,
+                            self.visible_bodies()[secondary].id),
+                        _ => "inertial".to_string(),
+                    },
+                    labels = match self.labels {
+                        Labels::Off => "off",
+                        Labels::Named => "named bodies",
+                        Labels::All => "all bodies",
+                    },
+                    forecast = if self.show_forecast {format!("{} steps", self.forecast_steps)} else {"off".to_string()},
+                    method = self.sim.integrator,
+                    placement = match self.placement {
+                        Placement::Free => "Free".to_string(),
+                        Placement::Circular => format!("Circular orbit ({})", if self.retrograde {"retrograde"} else {"prograde"}),
+                        Placement::Eccentric => format!("Orbit e = {} ({})", self.eccentricity, if self.retrograde {"retrograde"} else {"prograde"}),
+                    },
                     sim_speed = self.fast_forward,
-                    step_size = self.step_size,
+                    step_size = self.sim.step_size,
+                    time = self.sim.time,
+                    time_unit = self.units.time(),
+                    units = self.units.name(),
+                    g = self.sim.forces.g,
+                    k = self.sim.forces.k,
+                    reversed = match self.merge_warning {
+                        Some(t) => format!(" (reversed, merge at t = {} {} can't be reversed)", t, self.units.time()),
+                        None if self.sim.is_reversed() => " (reversed)".to_string(),
+                        None => String::new(),
+                    },
                     inputtype = input_display,
-                    inbuffer = self.input_buffer.chars().skip(1).collect::<String>());
+                    inbuffer = self.input_buffer.chars().skip(1).collect::<String>(),
+                    snapshots = (0..SLOTS)
+                        .filter(|slot| self.snapshots.is_filled(*slot))
+                        .map(|slot| (slot + 1).to_string())
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                    recording = match &self.recorder {
+                        None => "off".to_string(),
+                        Some(recorder) => format!("{} ({} frames)", recorder.path().display(), recorder.frames()),
+                    },
+                    replay = match &self.replay {
+                        None => format!("off ({} frames)", self.history.len()),
+                        Some(replay) => format!(
+                            "{} {}/{} t={} {} speed {}x",
+                            if replay.playing {"playing"} else {"paused"},
+                            replay.index() + 1,
+                            self.history.len(),
+                            self.history.get(replay.index()).map_or(0.0, |frame| frame.time),
+                            self.units.time(),
+                            replay.speed),
+                    });
 
                 let text = graphics::Text::new(info);
                 graphics::draw(ctx, &text, graphics::DrawParam::new()).expect("error drawing text");
+
+                if self.pattern_menu { //pattern dialog below the top left text
+                    let pattern = &self.pattern;
+                    let dialog = format!(
+                        "
+                    Spawn pattern (G to spawn at the cursor, N to close)
+                    1 - Shape: {shape:?} ({count} bodies)
+                    2 - Rows: {rows}
+                    3 - Columns: {columns}
+                    4 - Spacing: {spacing} {length}
+                    5 - Jitter: {jitter} x spacing
+                    6 - Velocity: {velocity}
+                    7 - Speed: {speed} {speed_unit}
+                    8 - Direction: {direction} degrees
+                    ",
+                        shape = pattern.shape,
+                        count = pattern.limited().count(),
+                        rows = pattern.rows,
+                        columns = pattern.columns,
+                        spacing = pattern.spacing,
+                        length = self.units.length(),
+                        jitter = pattern.jitter,
+                        velocity = match pattern.velocity {
+                            VelocityField::Zero => "zero",
+                            VelocityField::Uniform => "uniform",
+                            VelocityField::Rotation => "rigid rotation",
+                            VelocityField::Keplerian => "Keplerian shear",
+                        },
+                        speed = pattern.speed,
+                        speed_unit = match pattern.velocity {
+                            VelocityField::Rotation => format!("rad/{}", self.units.time()),
+                            _ => self.units.velocity(),
+                        },
+                        direction = pattern.direction.to_degrees());
+
+                    let text = graphics::Text::new(dialog);
+                    graphics::draw(ctx, &text, graphics::DrawParam::new().dest(Point2::new(0.0, 400.0))).expect("error drawing pattern dialog");
+                }
+
+                if !self.selection.is_empty() && !self.pattern_menu && !self.units_menu {
+                    let dialog = format!(
+                        "
+                    Selection: {count} bodies (Escape to clear)
+                    F1 - Delete
+                    F2 - Zero velocity
+                    F3 - Add velocity x
+                    F4 - Add velocity y
+                    F5 - Scale mass
+                    F6 - Set charge
+                    F7 - Anchor/release
+                    F8 - Copy (Ctrl+V pastes at the cursor)
+                    Alt+Shift+1-9 - Save as a stamp (Ctrl+Shift+1-9 pastes it), saved: {stamps}
+                    ",
+                        count = self.selection.len(),
+                        stamps = (0..SLOTS)
+                            .filter(|slot| self.stamps.is_filled(*slot))
+                            .map(|slot| (slot + 1).to_string())
+                            .collect::<Vec<String>>()
+                            .join(" "));
+
+                    let text = graphics::Text::new(dialog);
+                    graphics::draw(ctx, &text, graphics::DrawParam::new().dest(Point2::new(0.0, 400.0))).expect("error drawing selection dialog");
+                }
+
+                if self.units_menu {
+                    let dialog = format!(
+                        "
+                    Units (K to close)
+                    1 - System: {units} (length {length}, mass {mass}, time {time}, charge {charge})
+                    2 - G: {g} {length}^3/({mass} {time}^2)
+                    3 - k: {k} {mass} {length}^3/({time}^2 {charge}^2)
+                    ",
+                        units = self.units.name(),
+                        length = self.units.length(),
+                        mass = self.units.mass(),
+                        time = self.units.time(),
+                        charge = self.units.charge(),
+                        g = self.sim.forces.g,
+                        k = self.sim.forces.k);
+
+                    let text = graphics::Text::new(dialog);
+                    graphics::draw(ctx, &text, graphics::DrawParam::new().dest(Point2::new(0.0, 400.0))).expect("error drawing units dialog");
+                }
+
+                let panel = match (self.inspector(), self.orbit_info()) { //top right inspector and orbit panel
+                    (Some(inspector), orbit_info) => Some(inspector + &orbit_info.unwrap_or_default()),
+                    (None, orbit_info) => orbit_info,
+                };
+
+                if let Some(panel) = panel {
+                    let screen = graphics::screen_coordinates(ctx);
+                    let text = graphics::Text::new(panel);
+                    graphics::draw(ctx, &text, graphics::DrawParam::new().dest(Point2::new(screen.w - 420.0, 0.0))).expect("error drawing orbit info");
+                }
             }
 
+            //meshes are built in the view, which is the world itself unless it's co-rotating, then panned and zoomed here
             let params = graphics::DrawParam::new()
-                .dest(self.offset)
+                .dest(self.view_offset())
                 .scale(Vector2::new(self.zoom, self.zoom));
+            let view_pair = self.view_pair();
+            let axes = self.view_axes();
             
+            if self.potential_overlay != PotentialOverlay::Off {
+                self.draw_potential(ctx);
+            }
+            self.draw_field(ctx);
+
             let mut mesh = graphics::MeshBuilder::new();
 
-            for i in 0..self.bodies.len(){ //draw trail and bodies
-                if self.trail_length > 1 { //trail
+            let pair_trails = view_pair.map(|(anchor, primary, secondary)| (
+                anchor,
+                primary.trail.iter().cloned().collect::<Vec<Point2>>(),
+                secondary.trail.iter().cloned().collect::<Vec<Point2>>(),
+                (primary.mass, secondary.mass)));
+
+            let selected = self.selection.iter().cloned().collect::<HashSet<u64>>();
+
+            for body in self.visible_bodies().iter(){ //draw trail and bodies
+                if self.sim.trail_length > 1 { //trail
+                    let co_rotating_trail; //each point in the frame of its own step
+                    let trail = match &pair_trails {
+                        Some((anchor, primary, secondary, masses)) => {
+                            co_rotating_trail = co_rotating_path(&body.trail.iter().cloned().collect::<Vec<Point2>>(), primary, secondary, *masses, anchor);
+                            &co_rotating_trail[..]
+                        },
+                        None => body.trail.as_slices().0,
+                    };
+
                     let result = mesh.line(
-                        &self.bodies[i].trail.as_slices().0,
-                        0.25 * self.bodies[i].radius,
+                        trail,
+                        0.25 * body.radius,
                         graphics::Color::new(0.1, 0.25, 1.0, 0.5));
 
                     match result {
@@ -189,21 +1463,29 @@ impl event::EventHandler for MainState {
                 let mut r_val = 1.0;
                 let mut b_val = 1.0;
 
-                if self.bodies[i].charge < 0.0{
-                    r_val += self.bodies[i].charge / 5.0;
+                if body.charge < 0.0{
+                    r_val += body.charge / 5.0;
                 }else {
-                    b_val -= self.bodies[i].charge / 5.0;
+                    b_val -= body.charge / 5.0;
                 }
 
                 let g_val = 1.0 - (r_val - b_val).abs();
 
                 mesh.circle(
                     graphics::DrawMode::fill(),
-                    self.bodies[i].pos,
-                    self.bodies[i].radius,
+                    to_view(axes, body.pos),
+                    body.radius,
                     0.25,
                     graphics::Color::new(r_val, g_val, b_val, 1.0));
 
+                if body.anchored {
+                    mesh.circle(graphics::DrawMode::stroke(1.5 / self.zoom), to_view(axes, body.pos), body.radius + 2.0 / self.zoom, 0.25, graphics::Color::new(0.6, 0.6, 0.6, 1.0));
+                }
+
+                if selected.contains(&body.id) {
+                    mesh.circle(graphics::DrawMode::stroke(1.5 / self.zoom), to_view(axes, body.pos), body.radius + 5.0 / self.zoom, 0.25, graphics::Color::new(1.0, 0.9, 0.2, 0.9));
+                }
+
             }
 
             let built_mesh = mesh.build(ctx);
@@ -213,36 +1495,130 @@ impl event::EventHandler for MainState {
                 Err(_err) => {},
             }
 
+            if self.paused && self.replay.is_none() { //velocity arrows with a handle on the tip to drag
+                let frame = self.rotating_frame();
+                let mut mesh = graphics::MeshBuilder::new();
+                let mut empty = true;
+
+                for body in self.sim.bodies.iter() {
+                    let tip = self.velocity_tip(body, frame);
+                    if distance(tip, body.pos) > body.radius {
+                        let color = graphics::Color::new(1.0, 0.8, 0.2, 0.8);
+                        mesh.line(&[to_view(axes, body.pos), to_view(axes, tip)], 1.5 / self.zoom, color).expect("error building velocity arrow");
+                        mesh.circle(graphics::DrawMode::fill(), to_view(axes, tip), 4.0 / self.zoom, 0.25, color);
+                        empty = false;
+                    }
+                }
 
-            if self.mouse_pressed && self.predict_speed != 0{ // draw prediction
-                if self.predict_body.trail.len() > 2{
-                    let trail = graphics::Mesh::new_line(
-                        ctx,
-                        &self.predict_body.trail.as_slices().0,
-                        0.25 * self.predict_body.radius,
-                        graphics::Color::new(0.0, 1.0, 0.1, 0.4));
+                if !empty {
+                    let arrows = mesh.build(ctx).expect("error building velocity arrows");
+                    graphics::draw(ctx, &arrows, params).expect("error drawing velocity arrows");
+                }
+            }
+
+            if self.labels != Labels::Off { //labels stay the same size at any zoom, so they're placed in screen space
+                let view_offset = self.view_offset();
 
-                    match trail {
-                        Ok(line) => graphics::draw(ctx, &line, params).expect("error drawing trail"),
-                        Err(_error) => {},
+                for body in self.visible_bodies().iter() {
+                    let label = match (&body.name, self.labels) {
+                        (Some(name), _) => name.clone(),
+                        (None, Labels::All) => body.id.to_string(),
+                        (None, _) => continue,
                     };
+
+                    let center = view_offset + to_view(axes, body.pos).coords * self.zoom;
+                    let dest = center + Vector2::new(body.radius * self.zoom + 4.0, -8.0);
+                    graphics::queue_text(ctx, &graphics::Text::new(label), dest, Some(graphics::Color::new(1.0, 1.0, 1.0, 0.8)));
                 }
 
-                let body = graphics::Mesh::new_circle( //draw prediction body
-                    ctx,
-                    graphics::DrawMode::fill(),
-                    self.predict_body.pos,
-                    self.predict_body.radius,
-                    0.25,
-                    graphics::Color::new(0.0, 1.0, 0.0, 0.8)).expect("error building prediction body");
+                graphics::draw_queued_text(ctx, graphics::DrawParam::new()).expect("error drawing labels");
+            }
 
-                graphics::draw(ctx, &body, params).expect("error drawing prediction body");
+
+            match &self.forecaster.latest {
+                Some(forecast) if self.show_forecast && self.replay.is_none() => { //draw forecast ghosts as dashed lines
+                    let mut mesh = graphics::MeshBuilder::new();
+                    let mut empty = true;
+
+                    let path_of = |id: u64| forecast.paths.iter().find(|(path_id, _)| *path_id == id).map(|(_, path)| path);
+                    let pair_paths = view_pair.and_then(|(anchor, primary, secondary)|
+                        Some((anchor, path_of(primary.id)?, path_of(secondary.id)?, (primary.mass, secondary.mass))));
+
+                    for (_id, path) in forecast.paths.iter() {
+                        let path = match pair_paths {
+                            Some((anchor, primary, secondary, masses)) => co_rotating_path(path, primary, secondary, masses, &anchor),
+                            None => path.clone(),
+                        };
+
+                        for dash in path.chunks(FORECAST_DASH).step_by(2).filter(|dash| dash.len() > 1) {
+                            mesh.line(dash, 1.0 / self.zoom, graphics::Color::new(1.0, 1.0, 1.0, 0.35)).expect("error building forecast");
+                            empty = false;
+                        }
+                    }
+
+                    if !empty {
+                        let ghosts = mesh.build(ctx).expect("error building forecast");
+                        graphics::draw(ctx, &ghosts, params).expect("error drawing forecast");
+                    }
+                },
+                _ => {},
             }
 
-            if self.mouse_pos != self.start_point && self.mouse_pressed{ //draw preview vector
+            match &self.prediction {
+                Some(prediction) if self.predicting() && self.predict_speed != 0 => { // draw prediction
+                    let path = match (view_pair, prediction.tracked.as_slice()) {
+                        (Some((anchor, primary, secondary)), [(_, primary_path), (_, secondary_path)]) => {
+                            let steps = prediction.path.len().min(primary_path.len()).min(secondary_path.len()); //both started with the path
+                            co_rotating_path(&prediction.path[..steps], &primary_path[..steps], &secondary_path[..steps], (primary.mass, secondary.mass), &anchor)
+                        },
+                        _ => prediction.path.clone(),
+                    };
+
+                    if path.len() > 2{
+                        let trail = graphics::Mesh::new_line(
+                            ctx,
+                            &path,
+                            0.25 * self.radius,
+                            graphics::Color::new(0.0, 1.0, 0.1, 0.4));
+
+                        match trail {
+                            Ok(line) => graphics::draw(ctx, &line, params).expect("error drawing trail"),
+                            Err(_error) => {},
+                        };
+                    }
+
+                    if let (Some(body), Some(pos)) = (prediction.body(), path.last()) {
+                        let body = graphics::Mesh::new_circle( //draw prediction body
+                            ctx,
+                            graphics::DrawMode::fill(),
+                            *pos,
+                            body.radius,
+                            0.25,
+                            graphics::Color::new(0.0, 1.0, 0.0, 0.8)).expect("error building prediction body");
+
+                        graphics::draw(ctx, &body, params).expect("error drawing prediction body");
+                    }
+
+                    if let Some(impact) = prediction.impact.and(path.last().cloned()) { //draw impact marker, a cross inside a ring
+                        let size = self.radius * 1.5;
+                        let color = graphics::Color::new(1.0, 0.2, 0.1, 0.9);
+
+                        let mut mesh = graphics::MeshBuilder::new();
+                        mesh.circle(graphics::DrawMode::stroke(0.2 * self.radius), impact, size, 0.25, color);
+                        mesh.line(&[impact + Vector2::new(-size, -size), impact + Vector2::new(size, size)], 0.2 * self.radius, color).expect("error building impact marker");
+                        mesh.line(&[impact + Vector2::new(-size, size), impact + Vector2::new(size, -size)], 0.2 * self.radius, color).expect("error building impact marker");
+
+                        let marker = mesh.build(ctx).expect("error building impact marker");
+                        graphics::draw(ctx, &marker, params).expect("error drawing impact marker");
+                    }
+                },
+                _ => {},
+            }
+
+            if self.mouse_pos != self.start_point && self.mouse_pressed && self.placement == Placement::Free{ //draw preview vector
                 let line = graphics::Mesh::new_line(
                     ctx,
-                    &[self.start_point, self.mouse_pos][..],
+                    &[to_view(axes, self.start_point), to_view(axes, self.mouse_pos)][..],
                     0.25 * self.radius,
                     graphics::Color::new(1.0, 1.0, 1.0, 0.8))
                     .expect("error building preview line mesh");
@@ -250,17 +1626,50 @@ impl event::EventHandler for MainState {
                 graphics::draw(ctx, &line, params).expect("error drawing preview line");
             }
 
+            if let Some(marquee) = &self.marquee { //selection box or lasso
+                let mut polygon = marquee.polygon();
+                polygon.push(polygon[0]);
 
-            let outline = graphics::Mesh::new_circle( //draw outline
-                ctx,
-                graphics::DrawMode::fill(),
-                if self.mouse_pressed {self.start_point} else {self.mouse_pos},
-                self.radius,
-                2.0,
-                graphics::Color::new(1.0, 1.0, 1.0, 0.25))
-                .expect("error building outline");
+                if let Ok(outline) = graphics::Mesh::new_line(ctx, &polygon, 1.0 / self.zoom, graphics::Color::new(1.0, 0.9, 0.2, 0.8)) {
+                    graphics::draw(ctx, &outline, params).expect("error drawing selection");
+                }
+            }
 
-            graphics::draw(ctx, &outline, params).expect("error drawing outline");
+            match &self.replay {
+                None if self.grab.is_some() => {},
+
+                None => {
+                    let outline = graphics::Mesh::new_circle( //draw outline
+                        ctx,
+                        graphics::DrawMode::fill(),
+                        to_view(axes, if self.mouse_pressed {self.start_point} else {self.mouse_pos}),
+                        self.radius,
+                        2.0,
+                        graphics::Color::new(1.0, 1.0, 1.0, 0.25))
+                        .expect("error building outline");
+
+                    graphics::draw(ctx, &outline, params).expect("error drawing outline");
+                },
+
+                Some(replay) => { //draw timeline in screen coordinates
+                    let timeline = replay::timeline_rect(graphics::screen_coordinates(ctx));
+                    let mut played = timeline;
+                    played.w *= replay.progress(self.history.len());
+
+                    let mut mesh = graphics::MeshBuilder::new();
+                    mesh.rectangle(graphics::DrawMode::stroke(1.0), timeline, graphics::Color::new(1.0, 1.0, 1.0, 0.8));
+                    mesh.rectangle(graphics::DrawMode::fill(), played, graphics::Color::new(0.1, 0.25, 1.0, 0.8));
+                    mesh.circle(
+                        graphics::DrawMode::fill(),
+                        Point2::new(played.x + played.w, played.y + played.h / 2.0),
+                        played.h,
+                        0.25,
+                        graphics::Color::new(1.0, 1.0, 1.0, 1.0));
+
+                    let timeline_mesh = mesh.build(ctx).expect("error building timeline");
+                    graphics::draw(ctx, &timeline_mesh, graphics::DrawParam::new()).expect("error drawing timeline");
+                },
+            }
         }else {
             //if help_menu is true
             let help = "
@@ -278,11 +1687,29 @@ impl event::EventHandler for MainState {
 
                     Left click to place a body, dragging before releasing makes an initial velocity vector.
 
-                    Right click over a body to delete it.
+                    Right click over a body to delete it, right drag to box select, Shift+right drag to lasso select. F1-F8 then act on the selection
 
-                    G creates a 10x10 grid of bodies with the specified radii and densities.
+                    Ctrl+C/Ctrl+V to copy the selection and paste it at the cursor, Alt+Shift+1-9 to save it as a stamp, Ctrl+Shift+1-9 to paste a stamp
 
-                    R to reset.
+                    While paused, drag a body to move it or the tip of its velocity arrow to change it (Ctrl+drag pulls an arrow out of a still body)
+
+                    G spawns the current pattern at the cursor with the specified radii and densities, N opens the pattern dialog.
+
+                    R to reset the current scenario, M to pick a scenario.
+
+                    F to show forecast ghosts of every body's path, - and = to halve/double how many steps ahead they go
+
+                    C to cycle the potential overlay between off, heatmap and heatmap with contours
+
+                    J to cycle the field overlay between off, arrows and streamlines
+
+                    Tab to cycle the camera between free, following the selected body, the barycentre and the most massive body. Panning and zooming stay relative to the target
+
+                    / to cycle body labels between off, named bodies only and every body (unnamed bodies show their id)
+
+                    L to switch between the inertial frame and one co-rotating with the selected body and its primary (or the two heaviest bodies), which keeps the pair fixed on screen
+
+                    K opens the units dialog, to pick sandbox, SI or astronomical units or enter G and k.
 
                     Space to pause.
 
@@ -291,6 +1718,26 @@ impl event::EventHandler for MainState {
                     1 and 2 to change sim speed (affects performance, not precision)
 
                     3 and 4 to change step size (affects precision, not performance, lower is better)
+
+                    Ctrl+S to save the scene, Ctrl+O to load it (scene.json, or the file given on the command line)
+
+                    Ctrl+1-9 to save a snapshot, Shift+1-9 to restore it
+
+                    O to switch placement between free, circular orbit and eccentric orbit around the strongest attractor at the cursor
+
+                    [ and ] to change the orbit eccentricity, Y to switch between prograde and retrograde orbits
+
+                    Left click a body to select it and open the inspector, Alt+1-8 to edit its position, velocity, mass, radius, density or charge, Alt+9 to name it, Escape to deselect
+
+                    Alt+left click a body to show its orbit, Alt+right click to choose the primary it orbits
+
+                    U to reverse time (exact with Verlet, Euler drifts, merges can't be reversed)
+
+                    Ctrl+Z to undo placing, deleting or spawning bodies and changing settings, Ctrl+Y to redo
+
+                    P to enter/leave replay. While replaying, Space plays/pauses, 1 and 2 change replay speed, drag the timeline to scrub
+
+                    T to start/stop recording trajectories (trajectory.csv, or the file given with --record)
                 ";
 
             let text = graphics::Text::new(help);
@@ -299,34 +1746,59 @@ impl event::EventHandler for MainState {
 
         graphics::present(ctx).expect("error rendering");
 
-        if ggez::timer::ticks(ctx) % 60 == 0{
+        if ggez::timer::ticks(ctx).is_multiple_of(60){
             println!("FPS: {}", ggez::timer::fps(ctx));
-            println!("Bodies: {}", self.bodies.len());
+            println!("Bodies: {}", self.sim.bodies.len());
         }
         Ok(())
     }
 
-    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: event::MouseButton, x: f32, y: f32) {
-        let zoomed_x = (x - self.offset.x) * (1.0/self.zoom);
-        let zoomed_y = (y - self.offset.y) * (1.0/self.zoom);
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: event::MouseButton, x: f32, y: f32) {
+        let zoomed = self.to_world(x, y);
+
+        if let Some(replay) = &mut self.replay { //no editing during replay, only scrubbing
+            let screen = graphics::screen_coordinates(ctx);
+            if button == event::MouseButton::Left && replay::on_timeline(x, y, screen) {
+                replay.scrubbing = true;
+                replay.scrub_to(x, screen, self.history.len());
+            }
+            return;
+        }
+
+        if input::keyboard::active_mods(ctx).contains(input::keyboard::KeyMods::ALT) { //alt click picks the orbit panel's body and primary
+            let picked = self.body_under_mouse().map(|i| self.visible_bodies()[i].id);
+            match button {
+                event::MouseButton::Left => self.selected = picked,
+                event::MouseButton::Right => self.primary = picked,
+                _ => {},
+            };
+            return;
+        }
+
+        let grab = self.grab_at(zoomed, input::keyboard::active_mods(ctx).contains(input::keyboard::KeyMods::CTRL));
 
         match button {
+            event::MouseButton::Left if grab.is_some() => { //paused, so move the body or its velocity
+                self.selected = grab.as_ref().map(|(grab, _)| grab.id());
+                self.grab = grab;
+            },
+
+            event::MouseButton::Left if self.body_under_mouse().is_some() => { //select instead of placing a body on top of another
+                self.selected = self.body_under_mouse().map(|i| self.visible_bodies()[i].id);
+            },
+
             event::MouseButton::Left => {
-                self.start_point = Point2::new(zoomed_x, zoomed_y);
+                self.start_point = zoomed;
                 self.mouse_pressed = true;
             },
 
-            event::MouseButton::Right => {
-                println!("Removing body at {} {}", zoomed_x, zoomed_y);
-                self.bodies = self.bodies.par_iter() //iterate through meshes and delete any under mouse
-                    .filter_map(|body| {
-                        let mouse_pointer = Point2::new(zoomed_x, zoomed_y);
-                        if distance(mouse_pointer, body.pos) > body.radius {
-                            Some(body.clone())
-                        }else {
-                            None
-                        }
-                    }).collect();
+            event::MouseButton::Right => { //box or lasso select, or delete if the mouse doesn't move
+                let start = to_view(self.view_axes(), zoomed);
+                self.marquee = Some(if input::keyboard::active_mods(ctx).contains(input::keyboard::KeyMods::SHIFT) {
+                    Marquee::Lasso(vec![start])
+                }else {
+                    Marquee::Box(start, start)
+                });
             }
 
             _ => {},
@@ -334,17 +1806,32 @@ impl event::EventHandler for MainState {
     }
 
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: event::MouseButton, x: f32, y: f32) {
-        let zoomed_x = (&x - self.offset.x) * (1.0/self.zoom);
-        let zoomed_y = (&y - self.offset.y) * (1.0/self.zoom);
+        let zoomed = self.to_world(x, y);
+
+        if let Some(replay) = &mut self.replay {
+            replay.scrubbing = false;
+            return;
+        }
+
+        if self.grab.is_some() {
+            self.release_grab();
+            return;
+        }
+
+        if button == event::MouseButton::Right {
+            if let Some(marquee) = self.marquee.take() {
+                self.finish_marquee(marquee, zoomed);
+            }
+        }
 
-        if let event::MouseButton::Left = button{
-            self.bodies.push(Body::new(
+        if button == event::MouseButton::Left && self.mouse_pressed {
+            self.edit(Edit::AddBodies(vec![Body::new(
                     self.start_point,
                     self.radius.powi(3) * self.density,
                     self.charge, 
                     self.radius,
-                    Vector2::new((zoomed_x - self.start_point.x)/5.0 * self.zoom, (zoomed_y - self.start_point.y)/5.0 * self.zoom ))
-            );
+                    self.launch_velocity(self.start_point, zoomed))
+            ]));
         }
 
         self.mouse_pressed = false;
@@ -357,24 +1844,90 @@ impl event::EventHandler for MainState {
         let delta_zoom = self.zoom - prev_zoom;
         self.zoom = ((self.zoom * 100_000.0).round())/100_000.0;
 
-        let focus = Vector2::new(self.mouse_pos.x + self.offset.x, self.mouse_pos.y + self.offset.y) * delta_zoom;
+        let mouse_pos = to_view(self.view_axes(), self.mouse_pos);
+        let focus = (mouse_pos - self.camera.focus + self.offset.coords).coords * delta_zoom; //zoom about the cursor, relative to the followed target
         self.offset -= focus;
     }
 
-    fn text_input_event(&mut self, _ctx: &mut Context, character: char){
+    fn text_input_event(&mut self, ctx: &mut Context, character: char){
         match self.input_type{
             None => {},
 
+            _ if input::keyboard::active_mods(ctx).contains(input::keyboard::KeyMods::ALT) => {}, //the key that opened an inspector field
+
+            Some(InputVar::BodyName) => {
+                if !character.is_control() {
+                    self.input_buffer.push(character);
+                }
+            },
+
             _ => {
-                if character.is_digit(10) || character == '.'{
+                if character.is_ascii_digit() || character == '.' || character == '-' || character == 'e'{ //e for constants like 6.674e-11
                     self.input_buffer.push(character);
                 }
             }
         }
     }
 
-    fn key_down_event(&mut self, _ctx: &mut Context, keycode: input::keyboard::KeyCode, _keymods: input::keyboard::KeyMods, _repeat: bool){
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: input::keyboard::KeyCode, keymods: input::keyboard::KeyMods, _repeat: bool){
+        if self.input_type.is_none() && self.replay_key(keycode) {
+            return;
+        }
+
+        if self.replay.is_some() && !replay_allows(keycode) { //everything else would edit the live world hidden behind the replay
+            return;
+        }
+
+        if self.scenario_menu {
+            if let Some(scenario) = number_key(keycode).and_then(|i| SCENARIOS.get(i)) {
+                self.load_scenario(*scenario);
+                self.scenario_menu = false;
+            }else if keycode == input::keyboard::KeyCode::M || keycode == input::keyboard::KeyCode::Escape {
+                self.scenario_menu = false;
+            }
+            return;
+        }
+
+        let before = self.params();
+
+        if self.pattern_menu && self.input_type.is_none() {
+            self.pattern_key(keycode);
+        }else if self.units_menu && self.input_type.is_none() {
+            self.units_key(keycode);
+        }else if !self.selection.is_empty() && self.input_type.is_none() && self.selection_key(keycode, keymods) {
+            return;
+        }
+
         match self.input_type{
+            None if self.pattern_menu || self.units_menu => {},
+
+            None if keymods.contains(input::keyboard::KeyMods::ALT) => {
+                if let Some(field) = number_key(keycode) {self.inspect(field)}
+                return;
+            },
+
+            None if keymods.contains(input::keyboard::KeyMods::CTRL) => {
+                match keycode{
+                    input::keyboard::KeyCode::S => self.save_scene(),
+                    input::keyboard::KeyCode::O => self.load_scene(),
+                    input::keyboard::KeyCode::Z => self.undo(),
+                    input::keyboard::KeyCode::Y => self.redo(),
+                    input::keyboard::KeyCode::C => self.copy_selection(),
+                    input::keyboard::KeyCode::V => self.paste(&self.clipboard.clone()),
+                    _ => match number_key(keycode) {
+                        Some(slot) if keymods.contains(input::keyboard::KeyMods::SHIFT) => self.paste_stamp(slot),
+                        Some(slot) => self.save_snapshot(slot),
+                        None => {},
+                    },
+                };
+                return;
+            },
+
+            None if keymods.contains(input::keyboard::KeyMods::SHIFT) => {
+                if let Some(slot) = number_key(keycode) {self.restore_snapshot(slot)}
+                return;
+            },
+
             None => { 
                 self.offset.y += match keycode{
                     input::keyboard::KeyCode::Up => 50.0,
@@ -400,10 +1953,10 @@ impl event::EventHandler for MainState {
                     _ => 0.0,
                 };
 
-                self.trail_length = match keycode{
-                    input::keyboard::KeyCode::E => self.trail_length + 1,
-                    input::keyboard::KeyCode::D => if self.trail_length != 0 {self.trail_length - 1} else {0},
-                    _ => self.trail_length,
+                self.sim.trail_length = match keycode{
+                    input::keyboard::KeyCode::E => self.sim.trail_length + 1,
+                    input::keyboard::KeyCode::D => if self.sim.trail_length != 0 {self.sim.trail_length - 1} else {0},
+                    _ => self.sim.trail_length,
                 };
 
                 self.predict_speed = match keycode {
@@ -418,11 +1971,12 @@ impl event::EventHandler for MainState {
                     _ => self.fast_forward,
                 };
 
-                self.step_size += match keycode {
+                let step_change = match keycode { //changes the size, running backwards stays backwards
                     input::keyboard::KeyCode::Key3 => -0.05,
                     input::keyboard::KeyCode::Key4 => 0.05,
                     _ => 0.0,
                 };
+                self.sim.step_size = (self.sim.step_size.abs() + step_change).max(0.0).copysign(self.sim.step_size);
 
                 self.charge += match keycode {
                     input::keyboard::KeyCode::V => -0.5,
@@ -433,24 +1987,60 @@ impl event::EventHandler for MainState {
                 match keycode{ //misc keys
                     input::keyboard::KeyCode::Space => self.paused = !self.paused,
 
-                    input::keyboard::KeyCode::G => self.bodies.append(&mut grid(self.offset, self.radius, self.density, self.zoom)),
+                    input::keyboard::KeyCode::Escape => self.selected = None,
+
+                    input::keyboard::KeyCode::G => self.spawn_pattern(),
+
+                    input::keyboard::KeyCode::N => self.pattern_menu = true,
+
+                    input::keyboard::KeyCode::K => self.units_menu = true,
+
+                    input::keyboard::KeyCode::C => {
+                        self.potential_overlay = match self.potential_overlay {
+                            PotentialOverlay::Off => PotentialOverlay::Heatmap,
+                            PotentialOverlay::Heatmap => PotentialOverlay::Contours,
+                            PotentialOverlay::Contours => PotentialOverlay::Off,
+                        };
+                    },
+
+                    input::keyboard::KeyCode::J => {
+                        self.field_overlay = match self.field_overlay {
+                            FieldOverlay::Off => FieldOverlay::Arrows,
+                            FieldOverlay::Arrows => FieldOverlay::Streamlines,
+                            FieldOverlay::Streamlines => FieldOverlay::Off,
+                        };
+                    },
+
+                    input::keyboard::KeyCode::L => self.toggle_co_rotating(),
+
+                    input::keyboard::KeyCode::Slash => {
+                        self.labels = match self.labels {
+                            Labels::Off => Labels::Named,
+                            Labels::Named => Labels::All,
+                            Labels::All => Labels::Off,
+                        };
+                    },
+
+                    input::keyboard::KeyCode::Tab => self.cycle_follow(graphics::screen_coordinates(ctx)),
+
+                    input::keyboard::KeyCode::F => {
+                        self.show_forecast = !self.show_forecast;
+                        self.forecaster.clear();
+                    },
+
+                    input::keyboard::KeyCode::Minus => self.forecast_steps = (self.forecast_steps / 2).max(10),
+
+                    input::keyboard::KeyCode::Equals => self.forecast_steps = (self.forecast_steps * 2).min(20_000),
 
                     input::keyboard::KeyCode::R => {
-                        self.bodies = vec![
-                            Body::new(
-                                Point2::new(500.0, 400.0),
-                                300_000.0,
-                                0.0,
-                                100.0,
-                                Vector2::new(0.0, 0.0)),
-                        ];
-                        self.zoom = 1.0;
-                        self.offset = Point2::new(0.0, 0.0);
+                        self.load_scenario(self.scenario);
                         self.fast_forward = 1;
                     }
 
+                    input::keyboard::KeyCode::M => self.scenario_menu = true,
+
                     input::keyboard::KeyCode::I => {
-                        self.integrator = match self.integrator {
+                        self.sim.integrator = match self.sim.integrator {
                             Integrator::Euler => Integrator::Verlet,
                             Integrator::Verlet => Integrator::Euler,
                         };
@@ -458,6 +2048,26 @@ impl event::EventHandler for MainState {
 
                     input::keyboard::KeyCode::H => self.help_menu = !self.help_menu,
 
+                    input::keyboard::KeyCode::T => self.toggle_recording(),
+
+                    input::keyboard::KeyCode::P => self.toggle_replay(),
+
+                    input::keyboard::KeyCode::U => self.sim.reverse(),
+
+                    input::keyboard::KeyCode::O => {
+                        self.placement = match self.placement {
+                            Placement::Free => Placement::Circular,
+                            Placement::Circular => Placement::Eccentric,
+                            Placement::Eccentric => Placement::Free,
+                        };
+                    }
+
+                    input::keyboard::KeyCode::LBracket => self.eccentricity = (self.eccentricity - 0.05).max(0.0),
+
+                    input::keyboard::KeyCode::RBracket => self.eccentricity += 0.05,
+
+                    input::keyboard::KeyCode::Y => self.retrograde = !self.retrograde,
+
                     input::keyboard::KeyCode::Key0 => self.input_type = Some(InputVar::Density),
 
                     input::keyboard::KeyCode::Key9 => self.input_type = Some(InputVar::Radius),
@@ -479,6 +2089,10 @@ impl event::EventHandler for MainState {
                     self.input_buffer = self.input_buffer.chars().skip(1).collect();
 
                     match self.input_buffer.parse::<f32>(){
+                        _ if self.input_type == Some(InputVar::BodyName) => {
+                            let name = self.input_buffer.clone();
+                            self.edit_selected(|body| Ok(editor::rename(body, &name)));
+                        },
                         Err(_e) => {},
                         Ok(num) => {
                             match self.input_type{
@@ -486,8 +2100,21 @@ impl event::EventHandler for MainState {
                                 Some(InputVar::Radius) => self.radius = num,
                                 Some(InputVar::PredictSpeed) => self.predict_speed = num as usize,
                                 Some(InputVar::FastForward) => self.fast_forward = num as usize,
-                                Some(InputVar::StepSize) => self.step_size = num,
+                                Some(InputVar::StepSize) => self.set_step_size(num),
                                 Some(InputVar::Charge) => self.charge = num,
+                                Some(InputVar::PatternRows) => self.pattern.rows = pattern_count(num),
+                                Some(InputVar::PatternColumns) => self.pattern.columns = pattern_count(num),
+                                Some(InputVar::PatternSpacing) => self.pattern.spacing = num,
+                                Some(InputVar::PatternJitter) => self.pattern.jitter = num,
+                                Some(InputVar::PatternSpeed) => self.pattern.speed = num,
+                                Some(InputVar::PatternDirection) => self.pattern.direction = num.to_radians(),
+                                Some(InputVar::GravityConstant) => self.sim.forces.g = num,
+                                Some(InputVar::CoulombConstant) => self.sim.forces.k = num,
+                                Some(var) if var.body_field().is_some() => self.edit_selected(|body| editor::set_field(body, var.body_field().unwrap(), num)),
+                                Some(InputVar::SelectionVelocityX) => self.change_selection(GroupChange::AddVelocity(Vector2::new(num, 0.0))),
+                                Some(InputVar::SelectionVelocityY) => self.change_selection(GroupChange::AddVelocity(Vector2::new(0.0, num))),
+                                Some(InputVar::SelectionMassScale) => self.change_selection(GroupChange::ScaleMass(num)),
+                                Some(InputVar::SelectionCharge) => self.change_selection(GroupChange::SetCharge(num)),
                                 _ => {},
                             }
                         }
@@ -502,20 +2129,47 @@ impl event::EventHandler for MainState {
         if self.radius < 1.0 {self.radius = 1.0};
         self.radius = (self.radius * 1000.0).round()/1000.0;
         self.density = (self.density * 1000.0).round()/1000.0;
-        self.step_size = (self.step_size * 1000.0).round()/1000.0;
+        self.sim.step_size = (self.sim.step_size * 1000.0).round()/1000.0;
+        self.eccentricity = (self.eccentricity * 1000.0).round()/1000.0;
+
+        let after = self.params();
+        if after != before {
+            self.undo_stack.push(Edit::Params{before, after});
+        }
     }
 
 
-    fn mouse_motion_event(&mut self, ctx: &mut Context, _x: f32, _y: f32, dx: f32, dy: f32){
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32){
+        self.mouse_pos = self.to_world(x, y);
+
+        if let Some(replay) = &mut self.replay {
+            if replay.scrubbing {
+                replay.scrub_to(x, graphics::screen_coordinates(ctx), self.history.len());
+            }
+        }
 
-        //this is to make the line when creating a new body and create the preview body
-        if self.mouse_pressed {
-            self.predict_body = Body::new(
-                self.start_point,
+        let (view_pos, zoom) = (to_view(self.view_axes(), self.mouse_pos), self.zoom);
+        match &mut self.marquee {
+            Some(Marquee::Box(_, end)) => *end = view_pos,
+            Some(Marquee::Lasso(points)) if points.last().is_none_or(|last| distance(*last, view_pos) * zoom > 2.0) => points.push(view_pos),
+            _ => {},
+        }
+
+        if let Some((grab, _)) = self.grab { //preview the grabbed body from the rest of the world
+            self.drag_grabbed(grab);
+
+            let mut world = self.sim.clone();
+            self.prediction = world.remove_bodies(&[grab.id()]).pop().map(|body| self.predict(&world, body));
+        }else if self.predicting() { //this is to make the line when creating a new body and create the preview body
+            let pos = if self.mouse_pressed {self.start_point} else {self.mouse_pos};
+            let body = Body::new(
+                pos,
                 self.radius.powi(3) * self.density,
-                0.0,
+                self.charge,
                 self.radius,
-                Vector2::new((self.mouse_pos.x - self.start_point.x)/5.0 * self.zoom, (self.mouse_pos.y - self.start_point.y)/5.0 * self.zoom ))
+                self.launch_velocity(pos, self.mouse_pos));
+
+            self.prediction = Some(self.predict(&self.sim, body));
         }
 
         //move when holding middle click
@@ -526,31 +2180,54 @@ impl event::EventHandler for MainState {
     }
 }
 
+fn usage_error(msg: &str) -> ! {
+    eprintln!("error: {}", msg);
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
 pub fn main() -> GameResult{
+    let state = &mut MainState::new();
+
+    let mut args = env::args().skip(1);
+    let mut record = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--persist-snapshots" => state.snapshots = Snapshots::persistent(PathBuf::from("snapshots")),
+
+            "--record" => {
+                state.record_path = PathBuf::from(args.next().unwrap_or_else(|| usage_error("missing value for --record")));
+                record = true;
+            },
+
+            "--record-every" => {
+                let value = args.next().unwrap_or_else(|| usage_error("missing value for --record-every"));
+                state.record_every = value.parse::<usize>().unwrap_or_else(|_e| usage_error(&format!("invalid value for --record-every: {}", value)));
+            },
+
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            },
+
+            _ if arg.starts_with("--") => usage_error(&format!("unknown option {}", arg)),
+
+            _ => { //open a scene given on the command line
+                state.scene_path = PathBuf::from(arg);
+                state.load_scene();
+            },
+        }
+    }
+
+    if record {
+        state.toggle_recording();
+    }
+
     let (ctx, event_loop) = &mut ggez::ContextBuilder::new("N-body gravity sim", "Fish")
         .window_setup(ggez::conf::WindowSetup::default().title("N-body gravity sim"))
         .window_mode(ggez::conf::WindowMode::default().dimensions(1000.0, 800.0))
         .build().expect("error building context");
-    let state = &mut MainState::new();
 
     event::run(ctx, event_loop, state)
 }
-
-fn grid(start: Point2, radius: f32, density: f32, zoom: f32) -> Vec<Body> {
-    //create a 10x10 grid of bodies
-    let mut new_bodies: Vec<Body> = Vec::new();
-
-    (1..=10).for_each(|y|{
-        (1..=10).for_each(|x| {
-            let point = Point2::new((x as f32 * radius * 50.0) - (start.x * (1.0/zoom)), (y as f32 * radius * 50.0) - (start.y * (1.0/zoom)));
-            new_bodies.push(Body::new(
-                    point,
-                    radius.powi(3) * density,
-                    0.0,
-                    radius,
-                    Vector2::new(0.0, 0.0)));
-        });
-    });
-
-    new_bodies
-}
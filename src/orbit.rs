@@ -0,0 +1,149 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::physics::distance;
+
+use std::f32::consts::PI;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Debug, Copy, Clone)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f32, //negative for unbound (hyperbolic) orbits
+    pub eccentricity: f32,
+    pub argument_of_periapsis: f32, //radians from the +x axis
+    pub true_anomaly: f32, //radians from periapsis, in the direction of motion
+    pub period: Option<f32>, //None if the orbit isn't bound
+}
+
+fn cross(a: Vector2, b: Vector2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn strongest_pull(bodies: &[Body], pos: Point2, exclude: Option<usize>) -> Option<usize> {
+    bodies.iter()
+        .enumerate()
+        .filter(|(j, other)| Some(*j) != exclude && other.mass > 0.0 && distance(other.pos, pos) > 0.0)
+        .map(|(j, other)| (j, other.mass / distance(other.pos, pos).powi(2)))
+        .fold(None, |best: Option<(usize, f32)>, (j, pull)| match best {
+            Some((_, best_pull)) if best_pull >= pull => best,
+            _ => Some((j, pull)),
+        })
+        .map(|(j, _)| j)
+}
+
+pub fn dominant_attractor(bodies: &[Body], i: usize) -> Option<usize> { //the body pulling hardest on bodies[i]
+    strongest_pull(bodies, bodies[i].pos, Some(i))
+}
+
+pub fn dominant_attractor_at(bodies: &[Body], pos: Point2) -> Option<usize> {
+    strongest_pull(bodies, pos, None)
+}
+
+pub fn orbit_sense(bodies: &[Body], i: usize) -> f32 { //1.0 or -1.0, the direction bodies[i] orbits its own primary
+    let h = dominant_attractor(bodies, i)
+        .map_or(0.0, |j| cross(bodies[i].pos - bodies[j].pos, bodies[i].velocity - bodies[j].velocity));
+
+    if h < 0.0 {-1.0} else {1.0}
+}
+
+pub fn launch_velocity(bodies: &[Body], pos: Point2, mass: f32, eccentricity: f32, retrograde: bool, g: f32) -> Option<Vector2> {
+    //velocity that puts a body at pos on an orbit with pos as periapsis around the dominant attractor there,
+    //prograde means the same direction the primary itself orbits
+    let primary_i = dominant_attractor_at(bodies, pos)?;
+    let primary = &bodies[primary_i];
+
+    let r = pos - primary.pos;
+    let r_mag = r.norm();
+    let mu = g * (primary.mass + mass);
+    if mu <= 0.0 || r_mag == 0.0 {
+        return None;
+    }
+
+    let speed = (mu * (1.0 + eccentricity) / r_mag).sqrt(); //vis-viva at periapsis
+    let sense = orbit_sense(bodies, primary_i) * if retrograde {-1.0} else {1.0};
+    let tangent = Vector2::new(-r.y, r.x) / r_mag * sense;
+
+    Some(primary.velocity + tangent * speed)
+}
+
+pub fn orbital_elements(body: &Body, primary: &Body, g: f32) -> Option<OrbitalElements> { //two-body elements from state vectors, None if they're degenerate
+    let r = body.pos - primary.pos;
+    let v = body.velocity - primary.velocity;
+    let mu = g * (primary.mass + body.mass);
+    let r_mag = r.norm();
+
+    if mu <= 0.0 || r_mag == 0.0 {
+        return None;
+    }
+
+    let energy = v.norm_squared() / 2.0 - mu / r_mag;
+    let h = cross(r, v); //sign gives the direction of motion
+    let e_vec = (r * (v.norm_squared() - mu / r_mag) - v * r.dot(&v)) / mu;
+    let eccentricity = e_vec.norm();
+
+    let semi_major_axis = if energy != 0.0 { -mu / (2.0 * energy) } else { f32::INFINITY };
+
+    let (argument_of_periapsis, true_anomaly) = if eccentricity > 1e-6 {
+        let nu = cross(e_vec, r).atan2(e_vec.dot(&r));
+        (e_vec.y.atan2(e_vec.x), if h >= 0.0 { nu } else { -nu })
+    }else { //circular orbits have no periapsis, measure from the +x axis instead
+        (0.0, if h >= 0.0 { r.y.atan2(r.x) } else { -r.y.atan2(r.x) })
+    };
+
+    let period = if energy < 0.0 {
+        Some(2.0 * PI * (semi_major_axis.powi(3) / mu).sqrt())
+    }else {
+        None
+    };
+
+    Some(OrbitalElements {
+        semi_major_axis,
+        eccentricity,
+        argument_of_periapsis,
+        true_anomaly,
+        period,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G: f32 = 1.0;
+
+    fn primary() -> Body {
+        Body::new(Point2::new(0.0, 0.0), 1000.0, 0.0, 10.0, Vector2::new(0.0, 0.0))
+    }
+
+    #[test]
+    fn launched_orbits_have_the_asked_for_elements() {
+        let pos = Point2::new(100.0, 0.0);
+
+        for eccentricity in [0.0, 0.3, 0.7].iter() {
+            let bodies = vec![primary()];
+            let velocity = launch_velocity(&bodies, pos, 1.0, *eccentricity, false, G).expect("velocity");
+            let body = Body::new(pos, 1.0, 0.0, 1.0, velocity);
+
+            let elements = orbital_elements(&body, &bodies[0], G).expect("elements");
+            let a = 100.0 / (1.0 - eccentricity); //launched at periapsis
+            assert!((elements.eccentricity - eccentricity).abs() < 1e-3, "e = {}", elements.eccentricity);
+            assert!((elements.semi_major_axis - a).abs() < 1e-2 * a, "a = {}", elements.semi_major_axis);
+            assert!(elements.true_anomaly.abs() < 1e-2 || (elements.true_anomaly.abs() - 2.0 * PI).abs() < 1e-2);
+
+            let period = 2.0 * PI * (a.powi(3) / (G * 1001.0)).sqrt();
+            assert!((elements.period.expect("bound") - period).abs() < 1e-2 * period);
+        }
+    }
+
+    #[test]
+    fn escape_speed_is_unbound() {
+        let escape = (2.0 * G * 1001.0 / 100.0).sqrt();
+        let body = Body::new(Point2::new(100.0, 0.0), 1.0, 0.0, 1.0, Vector2::new(0.0, escape * 1.1));
+
+        let elements = orbital_elements(&body, &primary(), G).expect("elements");
+        assert!(elements.eccentricity > 1.0);
+        assert!(elements.semi_major_axis < 0.0);
+        assert!(elements.period.is_none());
+    }
+}
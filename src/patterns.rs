@@ -0,0 +1,234 @@
+use nalgebra as na;
+use crate::body::Body;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+
+type Vector2 = na::Vector2<f32>;
+
+pub const MAX_COUNT: usize = 100; //most rows or columns a pattern can have
+pub const MAX_BODIES: usize = 5000; //most bodies one pattern spawns, every step is O(N^2) in the body count
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Shape {
+    Grid, //rows x columns
+    Ring, //columns bodies on a circle of radius rows * spacing
+    Annulus, //rows rings around a hole as wide as the annulus
+    Disc, //rows rings around a central body
+    Line, //columns bodies along the direction
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VelocityField {
+    Zero,
+    Uniform, //speed along the direction
+    Rotation, //rigid rotation at speed radians per time unit
+    Keplerian, //circular orbit speed for the centre's mass at each radius, so the pattern shears
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pattern {
+    pub shape: Shape,
+    pub rows: usize,
+    pub columns: usize,
+    pub spacing: f32,
+    pub jitter: f32, //random offset as a fraction of the spacing
+    pub velocity: VelocityField,
+    pub speed: f32,
+    pub direction: f32, //radians from the +x axis, for lines and uniform velocity
+}
+
+impl Default for Pattern {
+    fn default() -> Self {
+        Pattern {
+            shape: Shape::Grid,
+            rows: 10,
+            columns: 10,
+            spacing: 50.0,
+            jitter: 0.0,
+            velocity: VelocityField::Zero,
+            speed: 0.0,
+            direction: 0.0,
+        }
+    }
+}
+
+impl Shape {
+    pub fn next(self) -> Shape {
+        match self {
+            Shape::Grid => Shape::Ring,
+            Shape::Ring => Shape::Annulus,
+            Shape::Annulus => Shape::Disc,
+            Shape::Disc => Shape::Line,
+            Shape::Line => Shape::Grid,
+        }
+    }
+}
+
+impl VelocityField {
+    pub fn next(self) -> VelocityField {
+        match self {
+            VelocityField::Zero => VelocityField::Uniform,
+            VelocityField::Uniform => VelocityField::Rotation,
+            VelocityField::Rotation => VelocityField::Keplerian,
+            VelocityField::Keplerian => VelocityField::Zero,
+        }
+    }
+}
+
+fn ring(radius: f32, count: usize) -> Vec<Vector2> {
+    (0..count)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / count as f32;
+            Vector2::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+fn ring_count(i: usize) -> usize { //bodies on the ith filled ring, as many as fit at the spacing
+    ((2.0 * PI * i as f32).round() as usize).max(1)
+}
+
+fn filled_rings(rings: std::ops::RangeInclusive<usize>, spacing: f32) -> Vec<Vector2> {
+    rings
+        .flat_map(|i| ring(i as f32 * spacing, ring_count(i)))
+        .collect()
+}
+
+impl Pattern {
+    pub fn count(&self) -> usize { //bodies the pattern lays out, before limited()
+        match self.shape {
+            Shape::Grid => self.rows.saturating_mul(self.columns),
+            Shape::Ring | Shape::Line => self.columns,
+            Shape::Annulus => (self.rows..=2 * self.rows).map(ring_count).sum(),
+            Shape::Disc => 1 + (1..=self.rows).map(ring_count).sum::<usize>(),
+        }
+    }
+
+    pub fn limited(&self) -> Pattern { //cut down to MAX_COUNT rows and columns and at most MAX_BODIES bodies
+        let mut pattern = *self;
+        pattern.rows = pattern.rows.min(MAX_COUNT);
+        pattern.columns = pattern.columns.min(MAX_COUNT);
+
+        while pattern.count() > MAX_BODIES {
+            match pattern.shape {
+                Shape::Grid if pattern.rows > pattern.columns => pattern.rows -= 1,
+                Shape::Grid | Shape::Ring | Shape::Line => pattern.columns -= 1,
+                Shape::Annulus | Shape::Disc => pattern.rows -= 1,
+            }
+        }
+
+        pattern
+    }
+
+    pub fn offsets(&self, seed: u64) -> Vec<Vector2> { //body positions relative to the pattern's centre, after limited()
+        self.limited().layout(seed)
+    }
+
+    fn layout(&self, seed: u64) -> Vec<Vector2> {
+        let direction = Vector2::new(self.direction.cos(), self.direction.sin());
+
+        let mut offsets = match self.shape {
+            Shape::Grid => {
+                let corner = Vector2::new(self.columns.saturating_sub(1) as f32, self.rows.saturating_sub(1) as f32) * self.spacing / 2.0;
+                (0..self.rows)
+                    .flat_map(|y| (0..self.columns).map(move |x| Vector2::new(x as f32, y as f32)))
+                    .map(|cell| cell * self.spacing - corner)
+                    .collect()
+            },
+            Shape::Ring => ring(self.rows as f32 * self.spacing, self.columns),
+            Shape::Annulus => filled_rings(self.rows..=2 * self.rows, self.spacing),
+            Shape::Disc => {
+                let mut offsets = vec![Vector2::new(0.0, 0.0)];
+                offsets.extend(filled_rings(1..=self.rows, self.spacing));
+                offsets
+            },
+            Shape::Line => (0..self.columns)
+                .map(|i| direction * (i as f32 - self.columns.saturating_sub(1) as f32 / 2.0) * self.spacing)
+                .collect(),
+        };
+
+        if self.jitter > 0.0 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            offsets.iter_mut().for_each(|offset| {
+                let angle = rng.gen_range(0.0, 2.0 * PI);
+                let r = self.jitter * self.spacing * rng.gen_range(0.0f32, 1.0).sqrt(); //even over a disc
+                *offset += Vector2::new(angle.cos(), angle.sin()) * r;
+            });
+        }
+
+        offsets
+    }
+
+    pub fn spawn(&self, template: &Body, center_body: Option<&Body>, g: f32, seed: u64) -> Vec<Body> {
+        //copies of template laid out around its position, rotation and Keplerian shear go around center_body
+        //if there is one, otherwise the pattern's own centre, in which case the Keplerian mass is the pattern's total
+        let center = template.pos;
+        let offsets = self.offsets(seed);
+
+        let (pivot, pivot_velocity, pivot_mass) = match center_body {
+            Some(body) => (body.pos, body.velocity, body.mass),
+            None => (center, Vector2::new(0.0, 0.0), template.mass * offsets.len() as f32),
+        };
+
+        offsets.iter()
+            .map(|offset| {
+                let pos = center + offset;
+                let r = pos - pivot;
+                let tangent = Vector2::new(-r.y, r.x);
+
+                let velocity = match self.velocity {
+                    VelocityField::Zero => Vector2::new(0.0, 0.0),
+                    VelocityField::Uniform => Vector2::new(self.direction.cos(), self.direction.sin()) * self.speed,
+                    VelocityField::Rotation => pivot_velocity + tangent * self.speed,
+                    VelocityField::Keplerian if r.norm() > 0.0 && pivot_mass > 0.0 =>
+                        pivot_velocity + tangent / r.norm() * (g * pivot_mass / r.norm()).sqrt(),
+                    VelocityField::Keplerian => pivot_velocity,
+                };
+
+                Body::new(pos, template.mass, template.charge, template.radius, velocity)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHAPES: [Shape; 5] = [Shape::Grid, Shape::Ring, Shape::Annulus, Shape::Disc, Shape::Line];
+
+    #[test]
+    fn count_matches_layout() {
+        for shape in SHAPES.iter() {
+            let pattern = Pattern {shape: *shape, rows: 7, columns: 5, ..Pattern::default()};
+            assert_eq!(pattern.offsets(0).len(), pattern.count(), "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn huge_patterns_are_limited() {
+        for shape in SHAPES.iter() {
+            let pattern = Pattern {shape: *shape, rows: 1_000_000_000, columns: 1_000_000_000, ..Pattern::default()};
+            let limited = pattern.limited();
+            assert!(limited.count() <= MAX_BODIES, "{:?} has {} bodies", shape, limited.count());
+            assert!(limited.rows <= MAX_COUNT && limited.columns <= MAX_COUNT);
+            assert_eq!(pattern.offsets(0).len(), limited.count());
+        }
+    }
+
+    #[test]
+    fn small_patterns_are_untouched() {
+        let pattern = Pattern::default();
+        assert_eq!(pattern.limited(), pattern);
+        assert_eq!(pattern.count(), 100);
+    }
+
+    #[test]
+    fn jitter_is_seeded() {
+        let pattern = Pattern {jitter: 0.5, ..Pattern::default()};
+        assert_eq!(pattern.offsets(3), pattern.offsets(3));
+        assert_ne!(pattern.offsets(3), pattern.offsets(4));
+    }
+}
@@ -1,7 +1,8 @@
-use ggez::nalgebra as na;
+use nalgebra as na;
 use std::f32::consts::PI;
 use crate::body::Body;
 use std::collections::HashSet;
+use std::str::FromStr;
 
 use rayon::prelude::*;
 
@@ -9,8 +10,23 @@ use rayon::prelude::*;
 type Point2 = na::Point2<f32>;
 type Vector2 = na::Vector2<f32>;
 
-const G: f32 = 6.674;
-const k: f32 = 900_000.0;
+pub const G: f32 = 6.674;
+pub const K: f32 = 900_000.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Forces{ //constants used by update_velocities_and_collide()
+    pub g: f32,
+    pub k: f32,
+}
+
+impl Default for Forces{
+    fn default() -> Self{
+        Forces{
+            g: G,
+            k: K,
+        }
+    }
+}
 
 pub fn collide(body1: &Body, body2: &Body) -> Body{ //inelastic collision that conserves momentum
     let body1_momentum = Vector2::new(body1.velocity.x * body1.mass, body1.velocity.y * body1.mass);
@@ -27,13 +43,18 @@ pub fn collide(body1: &Body, body2: &Body) -> Body{ //inelastic collision that c
 
     let new_rad = ( ((3.0/4.0)*total_volume)/PI ).powf(1.0/3.0); //add volumes
 
-    Body::new(
+    let mut merged = Body::new(
         if body1.radius > body2.radius {Point2::new(body1.pos.x, body1.pos.y)} else {Point2::new(body2.pos.x, body2.pos.y)}, //take position of bigger body
         total_mass,
         body1.charge + body2.charge,
         new_rad,
         total_momentum/total_mass,
-    )
+    );
+    merged.anchored = body1.anchored || body2.anchored;
+    merged.parents = Some((body1.id, body2.id));
+    merged.name = if body1.radius > body2.radius {body1.name.clone().or_else(|| body2.name.clone())} else {body2.name.clone().or_else(|| body1.name.clone())};
+
+    merged
 }
 
 pub fn distance(a: Point2, b: Point2) -> f32{
@@ -47,7 +68,31 @@ pub fn angle(a: Point2, b: Point2) -> f32{
     else {restricted_dom}
 }
 
-pub fn update_velocities_and_collide(bodies: &Vec<Body>, method: Integrator, step_size: f32) -> Vec<Body>{
+pub fn pair_accel(other_body: &Body, pos: Point2, mass: f32, charge: f32, forces: Forces) -> Vector2{ //acceleration of a body at pos caused by other_body
+    let r = distance(other_body.pos, pos);
+    let g_mag = (forces.g * other_body.mass)/(r.powi(2)); //acceleration = Gm_2/r^2
+    let c_mag = -(forces.k * other_body.charge * charge)/(r.powi(2) * mass);
+    let angle = angle(other_body.pos, pos);
+
+    Vector2::new(angle.cos() * (g_mag + c_mag), angle.sin() * (g_mag + c_mag))
+}
+
+pub fn acceleration_at(bodies: &[Body], pos: Point2, mass: f32, charge: f32, radius: f32, forces: Forces) -> Vector2{
+    //acceleration of a body at any point, bodies it would be touching are skipped like update_velocities_and_collide() does
+    bodies.iter()
+        .filter(|other_body| distance(other_body.pos, pos) > other_body.radius + radius)
+        .fold(Vector2::new(0.0, 0.0), |acc, other_body|{
+            acc + pair_accel(other_body, pos, mass, charge, forces)
+        })
+}
+
+pub fn accelerations(bodies: &[Body], forces: Forces) -> Vec<Vector2>{ //same accelerations update_velocities_and_collide() uses, without stepping
+    bodies.par_iter()
+        .map(|current_body| acceleration_at(bodies, current_body.pos, current_body.mass, current_body.charge, current_body.radius, forces))
+        .collect()
+}
+
+pub fn update_velocities_and_collide(bodies: &[Body], method: Integrator, step_size: f32, forces: Forces) -> Vec<Body>{
         let bodies_clone = bodies.to_owned();
         let mut bodies = bodies.to_owned();
 
@@ -63,19 +108,19 @@ pub fn update_velocities_and_collide(bodies: &Vec<Body>, method: Integrator, ste
                         if r <= other_body.radius + current_body.radius{
                             current_body.collision = Some(other_i);
                         }else{
-                            let g_mag = (G * other_body.mass)/(r.powi(2)); //acceleration = Gm_2/r^2
-                            let c_mag = (k * other_body.charge * current_body.charge * -1.0)/(r.powi(2) * current_body.mass);
-                            let angle = angle(other_body.pos, current_body.pos);
-
-                            current_body.current_accel += Vector2::new(angle.cos() * (g_mag + c_mag), angle.sin() * (g_mag + c_mag));
+                            current_body.current_accel += pair_accel(other_body, current_body.pos, current_body.mass, current_body.charge, forces);
                         }
                     });
 
                 current_body.update_trail();
-                match method{
-                    Integrator::Euler => current_body.update_euler(step_size),
-                    Integrator::Verlet => current_body.update_verlet(step_size),
-                };
+                if current_body.anchored {
+                    current_body.velocity = Vector2::new(0.0, 0.0);
+                }else {
+                    match method{
+                        Integrator::Euler => current_body.update_euler(step_size),
+                        Integrator::Verlet => current_body.update_verlet(step_size),
+                    };
+                }
             });
         
         
@@ -84,14 +129,12 @@ pub fn update_velocities_and_collide(bodies: &Vec<Body>, method: Integrator, ste
         //because there are duplicate collisions we need a set to keep track
         (0..bodies.len()).for_each(|i|{ 
             match bodies[i].collision {
-                None => {},
-                Some(index) => {
-                    if !collided.contains(&index) && i != index{
-                        bodies.push(collide(&bodies[i], &bodies[index]));
-                        collided.insert(index);
-                        collided.insert(i);
-                    }
-                }
+                Some(index) if !collided.contains(&index) && i != index => {
+                    bodies.push(collide(&bodies[i], &bodies[index]));
+                    collided.insert(index);
+                    collided.insert(i);
+                },
+                _ => {},
             }
         });
 
@@ -112,8 +155,20 @@ pub fn update_velocities_and_collide(bodies: &Vec<Body>, method: Integrator, ste
         }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Integrator{
     Euler,
     Verlet,
 }
+
+impl FromStr for Integrator{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>{
+        match s.to_lowercase().as_str(){
+            "euler" => Ok(Integrator::Euler),
+            "verlet" => Ok(Integrator::Verlet),
+            _ => Err(format!("unknown integrator \"{}\"", s)),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::simulation::Simulation;
+
+type Point2 = na::Point2<f32>;
+
+pub const PREDICTION_LIMIT: usize = 20_000; //steps, so a stable orbit doesn't grow the path forever
+
+pub struct Prediction { //a body's future path, stepped on a copy of the world with the same physics as the real one
+    pub world: Simulation,
+    pub id: u64,
+    pub path: Vec<Point2>,
+    pub impact: Option<Point2>, //where the body hit another one, the prediction stops there
+    pub tracked: Vec<(u64, Vec<Point2>)>, //paths of other bodies recorded alongside, for drawing in a moving frame
+}
+
+impl Prediction {
+    pub fn new(sim: &Simulation, body: Body) -> Self {
+        let mut world = sim.clone();
+        world.trail_length = 2; //the path is kept separately, the world's trails are never drawn
+
+        let id = body.id;
+        let path = vec![body.pos];
+        world.add_body(body);
+
+        Prediction {
+            world,
+            id,
+            path,
+            impact: None,
+            tracked: Vec::new(),
+        }
+    }
+
+    pub fn track(&mut self, id: u64) { //record another body's path from the next step on, a body that merges stops short
+        if let Some(body) = self.world.bodies.iter().find(|body| body.id == id) {
+            self.tracked.push((id, vec![body.pos]));
+        }
+    }
+
+    pub fn body(&self) -> Option<&Body> { //None after the impact
+        self.world.bodies.iter().find(|body| body.id == self.id)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.impact.is_some() || self.path.len() >= PREDICTION_LIMIT
+    }
+
+    pub fn advance(&mut self, steps: usize) {
+        for _ in 0..steps {
+            if self.is_finished() {
+                return;
+            }
+
+            self.world.step(1);
+
+            //collisions are found before bodies move, so the body was already touching at its last position
+            match self.body().map(|body| body.pos) {
+                Some(pos) => self.path.push(pos),
+                None => self.impact = self.path.last().cloned(),
+            }
+
+            if self.impact.is_none() {
+                for (id, path) in self.tracked.iter_mut() {
+                    if let Some(body) = self.world.bodies.iter().find(|body| body.id == *id) {
+                        path.push(body.pos);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::simulation::Simulation;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub const BINARY_MAGIC: &[u8; 8] = b"NBODYTRJ";
+pub const BINARY_VERSION: u32 = 2;
+
+const CSV_HEADER: &str = "time,id,x,y,vx,vy,mass,charge,radius,parent_a,parent_b";
+const NO_PARENT: u64 = u64::MAX; //binary parent ids of a body that didn't come from a merge, scene files can't use it as an id
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RecordFormat{
+    Csv,
+    Binary,
+}
+
+impl RecordFormat{
+    pub fn from_path(path: &Path) -> Self{ //.csv is text, anything else is the binary format
+        if path.extension().is_some_and(|ext| ext == "csv") {
+            RecordFormat::Csv
+        }else {
+            RecordFormat::Binary
+        }
+    }
+}
+
+/*
+binary layout, all little-endian:
+    header: magic "NBODYTRJ", u32 version, u32 cadence (steps per frame)
+    frame:  f32 time, u32 body count, then per body u64 id, u64 parent ids (NO_PARENT unless it came from a merge)
+            and f32 x, y, vx, vy, mass, charge, radius
+*/
+pub struct Recorder{
+    writer: BufWriter<File>,
+    path: PathBuf,
+    format: RecordFormat,
+    every: usize,
+    steps: usize,
+    frames: usize,
+}
+
+impl Recorder{
+    pub fn create<P: AsRef<Path>>(path: P, every: usize) -> io::Result<Self>{
+        let path = path.as_ref().to_path_buf();
+        let format = RecordFormat::from_path(&path);
+        let every = every.max(1);
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        match format{
+            RecordFormat::Csv => writeln!(writer, "{}", CSV_HEADER)?,
+            RecordFormat::Binary => {
+                writer.write_all(BINARY_MAGIC)?;
+                writer.write_all(&BINARY_VERSION.to_le_bytes())?;
+                writer.write_all(&(every as u32).to_le_bytes())?;
+            },
+        };
+
+        Ok(Recorder{
+            writer,
+            path,
+            format,
+            every,
+            steps: 0,
+            frames: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path{
+        &self.path
+    }
+
+    pub fn frames(&self) -> usize{
+        self.frames
+    }
+
+    pub fn record(&mut self, sim: &Simulation) -> io::Result<()>{ //call once per step, writes a frame every `every` steps
+        if self.steps.is_multiple_of(self.every) {
+            self.write_frame(sim)?;
+        }
+        self.steps += 1;
+
+        Ok(())
+    }
+
+    pub fn write_frame(&mut self, sim: &Simulation) -> io::Result<()>{
+        let writer = &mut self.writer;
+
+        match self.format{
+            RecordFormat::Csv => {
+                for body in sim.bodies.iter(){
+                    let (parent_a, parent_b) = body.parents.map_or((String::new(), String::new()), |(a, b)| (a.to_string(), b.to_string()));
+                    writeln!(writer, "{},{},{},{},{},{},{},{},{},{},{}",
+                        sim.time, body.id,
+                        body.pos.x, body.pos.y,
+                        body.velocity.x, body.velocity.y,
+                        body.mass, body.charge, body.radius,
+                        parent_a, parent_b)?;
+                }
+            },
+
+            RecordFormat::Binary => {
+                writer.write_all(&sim.time.to_le_bytes())?;
+                writer.write_all(&(sim.bodies.len() as u32).to_le_bytes())?;
+
+                for body in sim.bodies.iter(){
+                    writer.write_all(&body.id.to_le_bytes())?;
+                    let (parent_a, parent_b) = body.parents.unwrap_or((NO_PARENT, NO_PARENT));
+                    writer.write_all(&parent_a.to_le_bytes())?;
+                    writer.write_all(&parent_b.to_le_bytes())?;
+                    for value in &[body.pos.x, body.pos.y, body.velocity.x, body.velocity.y, body.mass, body.charge, body.radius]{
+                        writer.write_all(&value.to_le_bytes())?;
+                    }
+                }
+            },
+        };
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()>{
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Body;
+    use nalgebra::{Point2, Vector2};
+    use std::convert::TryInto;
+    use std::fs;
+    use std::process;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nbodyrs_{}_{}", process::id(), name))
+    }
+
+    fn sim() -> Simulation {
+        let mut merged = Body::new(Point2::new(1.0, 2.0), 5.0, -1.0, 3.0, Vector2::new(0.5, -0.5));
+        merged.parents = Some((3, 4));
+        Simulation::new(vec![merged, Body::new(Point2::new(100.0, 0.0), 1.0, 0.0, 1.0, Vector2::new(0.0, 1.0))])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], at: usize) -> u64 {
+        u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+    }
+
+    fn f32_at(bytes: &[u8], at: usize) -> f32 {
+        f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(RecordFormat::from_path(Path::new("out.csv")), RecordFormat::Csv);
+        assert_eq!(RecordFormat::from_path(Path::new("out.trj")), RecordFormat::Binary);
+        assert_eq!(RecordFormat::from_path(Path::new("out")), RecordFormat::Binary);
+    }
+
+    #[test]
+    fn binary_layout() {
+        let path = temp_path("layout.trj");
+        let sim = sim();
+
+        let mut recorder = Recorder::create(&path, 2).expect("create");
+        for _ in 0..3 { //steps 0 and 2 are written
+            recorder.record(&sim).expect("record");
+        }
+        assert_eq!(recorder.frames(), 2);
+        recorder.finish().expect("finish");
+
+        let bytes = fs::read(&path).expect("read");
+        fs::remove_file(&path).ok();
+
+        let body_size = 8 + 2 * 8 + 7 * 4;
+        let frame_size = 4 + 4 + sim.bodies.len() * body_size;
+        assert_eq!(bytes.len(), 16 + 2 * frame_size);
+
+        assert_eq!(&bytes[0..8], BINARY_MAGIC);
+        assert_eq!(u32_at(&bytes, 8), BINARY_VERSION);
+        assert_eq!(u32_at(&bytes, 12), 2);
+
+        assert_eq!(f32_at(&bytes, 16), sim.time);
+        assert_eq!(u32_at(&bytes, 20), sim.bodies.len() as u32);
+
+        for (i, body) in sim.bodies.iter().enumerate() {
+            let at = 24 + i * body_size;
+            let (parent_a, parent_b) = body.parents.unwrap_or((NO_PARENT, NO_PARENT));
+            assert_eq!(u64_at(&bytes, at), body.id);
+            assert_eq!(u64_at(&bytes, at + 8), parent_a);
+            assert_eq!(u64_at(&bytes, at + 16), parent_b);
+
+            let values = [body.pos.x, body.pos.y, body.velocity.x, body.velocity.y, body.mass, body.charge, body.radius];
+            for (j, value) in values.iter().enumerate() {
+                assert_eq!(f32_at(&bytes, at + 24 + j * 4), *value);
+            }
+        }
+    }
+
+    #[test]
+    fn csv_layout() {
+        let path = temp_path("layout.csv");
+        let sim = sim();
+
+        let mut recorder = Recorder::create(&path, 1).expect("create");
+        recorder.record(&sim).expect("record");
+        recorder.finish().expect("finish");
+
+        let text = fs::read_to_string(&path).expect("read");
+        fs::remove_file(&path).ok();
+
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), 1 + sim.bodies.len());
+
+        let merged = lines[1].split(',').collect::<Vec<&str>>();
+        assert_eq!(merged.len(), CSV_HEADER.split(',').count());
+        assert_eq!(merged[1], sim.bodies[0].id.to_string());
+        assert_eq!(&merged[9..], &["3", "4"]);
+
+        let plain = lines[2].split(',').collect::<Vec<&str>>();
+        assert_eq!(&plain[9..], &["", ""]);
+    }
+}
@@ -0,0 +1,78 @@
+use ggez::graphics;
+
+const TIMELINE_MARGIN: f32 = 50.0;
+const TIMELINE_HEIGHT: f32 = 12.0;
+
+pub struct Replay{
+    pub frame: f32, //fractional so speeds below 1 frame per update work
+    pub speed: f32,
+    pub playing: bool,
+    pub scrubbing: bool,
+}
+
+impl Replay{
+    pub fn new() -> Self{
+        Replay{
+            frame: 0.0,
+            speed: 1.0,
+            playing: true,
+            scrubbing: false,
+        }
+    }
+
+    pub fn update(&mut self, frames: usize){
+        let last = frames.saturating_sub(1) as f32;
+
+        if self.playing && !self.scrubbing {
+            self.frame += self.speed;
+            if self.frame >= last {
+                self.playing = false;
+            }
+        }
+
+        self.frame = self.frame.clamp(0.0, last);
+    }
+
+    pub fn index(&self) -> usize{
+        self.frame as usize
+    }
+
+    pub fn toggle_playing(&mut self, frames: usize){
+        if !self.playing && self.index() + 1 >= frames { //replay from the start once the end is reached
+            self.frame = 0.0;
+        }
+        self.playing = !self.playing;
+    }
+
+    pub fn slower(&mut self){
+        self.speed = (self.speed / 2.0).max(0.125);
+    }
+
+    pub fn faster(&mut self){
+        self.speed = (self.speed * 2.0).min(64.0);
+    }
+
+    pub fn scrub_to(&mut self, x: f32, screen: graphics::Rect, frames: usize){
+        let timeline = timeline_rect(screen);
+        let fraction = ((x - timeline.x) / timeline.w).clamp(0.0, 1.0);
+        self.frame = (fraction * frames.saturating_sub(1) as f32).round();
+    }
+
+    pub fn progress(&self, frames: usize) -> f32{
+        if frames > 1 {self.frame / (frames - 1) as f32} else {1.0}
+    }
+}
+
+pub fn timeline_rect(screen: graphics::Rect) -> graphics::Rect{ //bar along the bottom of the window, in screen coordinates
+    graphics::Rect::new(
+        TIMELINE_MARGIN,
+        screen.h - TIMELINE_MARGIN,
+        screen.w - 2.0 * TIMELINE_MARGIN,
+        TIMELINE_HEIGHT)
+}
+
+pub fn on_timeline(x: f32, y: f32, screen: graphics::Rect) -> bool{ //a little slack vertically so the bar is easy to grab
+    let timeline = timeline_rect(screen);
+    x >= timeline.x - TIMELINE_HEIGHT && x <= timeline.x + timeline.w + TIMELINE_HEIGHT
+        && y >= timeline.y - TIMELINE_HEIGHT && y <= timeline.y + 2.0 * TIMELINE_HEIGHT
+}
@@ -0,0 +1,257 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::generators::{self, ClusterParams, CollisionParams, DiskParams};
+use crate::physics::G;
+use crate::scene::Scene;
+use crate::simulation::Simulation;
+
+use std::f32::consts::PI;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+const CENTER: (f32, f32) = (500.0, 400.0); //middle of the default window
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Scenario {
+    Default,
+    InnerSolarSystem,
+    BinaryStar,
+    FigureEight,
+    Trojans,
+    Pythagorean,
+    Galaxy,
+    GalaxyCollision,
+    PlummerCluster,
+    ColdCollapse,
+}
+
+pub const SCENARIOS: [Scenario; 10] = [
+    Scenario::Default,
+    Scenario::InnerSolarSystem,
+    Scenario::BinaryStar,
+    Scenario::FigureEight,
+    Scenario::Trojans,
+    Scenario::Pythagorean,
+    Scenario::Galaxy,
+    Scenario::GalaxyCollision,
+    Scenario::PlummerCluster,
+    Scenario::ColdCollapse,
+];
+
+impl Scenario {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::Default => "Single massive body",
+            Scenario::InnerSolarSystem => "Inner solar system",
+            Scenario::BinaryStar => "Binary star with circumbinary planets",
+            Scenario::FigureEight => "Figure-eight three-body orbit",
+            Scenario::Trojans => "Trojan bodies at L4/L5",
+            Scenario::Pythagorean => "Pythagorean three-body problem",
+            Scenario::Galaxy => "Disk galaxy",
+            Scenario::GalaxyCollision => "Galaxy collision",
+            Scenario::PlummerCluster => "Plummer star cluster",
+            Scenario::ColdCollapse => "Cold collapse",
+        }
+    }
+
+    pub fn build(&self) -> Scene {
+        match self {
+            Scenario::Default => default(),
+            Scenario::InnerSolarSystem => inner_solar_system(),
+            Scenario::BinaryStar => binary_star(),
+            Scenario::FigureEight => figure_eight(),
+            Scenario::Trojans => trojans(),
+            Scenario::Pythagorean => pythagorean(),
+            Scenario::Galaxy => galaxy(),
+            Scenario::GalaxyCollision => galaxy_collision(),
+            Scenario::PlummerCluster => plummer_cluster(),
+            Scenario::ColdCollapse => cold_collapse(),
+        }
+    }
+}
+
+fn center() -> Point2 {
+    Point2::new(CENTER.0, CENTER.1)
+}
+
+fn scene(bodies: Vec<Body>, step_size: f32, trail_length: usize, zoom: f32) -> Scene { //zoomed around the center of the window
+    let mut sim = Simulation::new(bodies);
+    sim.step_size = step_size;
+    sim.trail_length = trail_length;
+
+    let mut scene = Scene::new(sim);
+    scene.zoom = zoom;
+    scene.offset = center() - center().coords * zoom;
+    scene
+}
+
+fn zero_momentum(bodies: &mut [Body]) { //move to the barycentre frame so the system doesn't drift
+    let total_mass: f32 = bodies.iter().map(|body| body.mass).sum();
+    let momentum = bodies.iter().fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.velocity * body.mass);
+    let drift = momentum / total_mass;
+
+    bodies.iter_mut().for_each(|body| body.velocity -= drift);
+}
+
+fn from_unitless(bodies: &[(f32, [f32; 2], [f32; 2])], length: f32, mass: f32, radius: f32) -> Vec<Body> {
+    //(mass, position, velocity) in units where G = 1, scaled to `length` and `mass` in sandbox units
+    let speed = (G * mass / length).sqrt();
+
+    bodies.iter()
+        .map(|(m, pos, vel)| Body::new(
+            center() + Vector2::new(pos[0], pos[1]) * length,
+            m * mass,
+            0.0,
+            radius,
+            Vector2::new(vel[0], vel[1]) * speed))
+        .collect()
+}
+
+fn default() -> Scene {
+    scene(vec![
+        Body::new(center(), 300_000.0, 0.0, 100.0, Vector2::new(0.0, 0.0)),
+    ], 1.0, 30, 1.0)
+}
+
+fn inner_solar_system() -> Scene {
+    //name, semi-major axis (AU), eccentricity, longitude of perihelion (degrees), mass (solar masses), radius
+    const PLANETS: [(&str, f32, f32, f32, f32, f32); 4] = [
+        ("Mercury", 0.387, 0.2056, 77.46, 1.66e-7, 3.0),
+        ("Venus", 0.723, 0.0068, 131.6, 2.45e-6, 5.0),
+        ("Earth", 1.000, 0.0167, 102.9, 3.00e-6, 5.0),
+        ("Mars", 1.524, 0.0934, 336.1, 3.23e-7, 4.0),
+    ];
+    const AU: f32 = 250.0;
+    const SUN_MASS: f32 = 300_000.0;
+
+    let mut bodies = vec![Body::new(center(), SUN_MASS, 0.0, 25.0, Vector2::new(0.0, 0.0)).with_name("Sun")];
+
+    for (name, a, e, perihelion, mass, radius) in PLANETS.iter() {
+        //start every planet at perihelion
+        let r = a * (1.0 - e) * AU;
+        let speed = (G * SUN_MASS * (1.0 + e) / r).sqrt();
+        let angle = perihelion.to_radians();
+        let direction = Vector2::new(angle.cos(), angle.sin());
+
+        bodies.push(Body::new(
+            center() + direction * r,
+            mass * SUN_MASS,
+            0.0,
+            *radius,
+            Vector2::new(-direction.y, direction.x) * speed).with_name(name));
+    }
+
+    zero_momentum(&mut bodies);
+    scene(bodies, 0.02, 200, 1.0)
+}
+
+fn binary_star() -> Scene {
+    const STAR_MASS: f32 = 150_000.0;
+    const SEPARATION: f32 = 100.0;
+    const PLANETS: [(f32, f32); 2] = [(330.0, 0.0), (480.0, PI)]; //orbit radius, starting angle
+
+    let star_speed = (G * 2.0 * STAR_MASS / SEPARATION).sqrt() / 2.0;
+    let mut bodies = vec![
+        Body::new(center() + Vector2::new(SEPARATION / 2.0, 0.0), STAR_MASS, 0.0, 15.0, Vector2::new(0.0, star_speed)).with_name("Star A"),
+        Body::new(center() - Vector2::new(SEPARATION / 2.0, 0.0), STAR_MASS, 0.0, 15.0, Vector2::new(0.0, -star_speed)).with_name("Star B"),
+    ];
+
+    for (r, angle) in PLANETS.iter() { //circular orbits around the pair's total mass
+        let speed = (G * 2.0 * STAR_MASS / r).sqrt();
+        let direction = Vector2::new(angle.cos(), angle.sin());
+
+        bodies.push(Body::new(
+            center() + direction * *r,
+            2.0,
+            0.0,
+            4.0,
+            Vector2::new(-direction.y, direction.x) * speed));
+    }
+
+    zero_momentum(&mut bodies);
+    scene(bodies, 0.02, 200, 0.8)
+}
+
+fn figure_eight() -> Scene {
+    //Chenciner & Montgomery (2000), initial conditions from Simo
+    let bodies = from_unitless(&[
+        (1.0, [0.970_004_4, -0.243_087_53], [0.466_203_7, 0.432_365_73]),
+        (1.0, [-0.970_004_4, 0.243_087_53], [0.466_203_7, 0.432_365_73]),
+        (1.0, [0.0, 0.0], [-0.932_407_4, -0.864_731_46]),
+    ], 250.0, 100_000.0, 8.0);
+
+    scene(bodies, 0.01, 400, 1.0)
+}
+
+fn trojans() -> Scene {
+    const SUN_MASS: f32 = 300_000.0;
+    const PLANET_MASS: f32 = 300.0; //mass ratio well under the 1/25 L4/L5 stability limit
+    const R: f32 = 300.0;
+
+    let mu = PLANET_MASS / (SUN_MASS + PLANET_MASS);
+    let omega = (G * (SUN_MASS + PLANET_MASS) / R.powi(3)).sqrt();
+    let co_rotating = |offset: Vector2| Vector2::new(-offset.y, offset.x) * omega; //velocity of a point fixed in the rotating frame
+
+    let sun = Vector2::new(-mu * R, 0.0);
+    let planet = Vector2::new((1.0 - mu) * R, 0.0);
+    let mut bodies = vec![
+        Body::new(center() + sun, SUN_MASS, 0.0, 25.0, co_rotating(sun)).with_name("Sun"),
+        Body::new(center() + planet, PLANET_MASS, 0.0, 8.0, co_rotating(planet)).with_name("Planet"),
+    ];
+
+    for side in [1.0, -1.0].iter() { //L4 leads the planet by 60 degrees, L5 trails it
+        let lagrange = Vector2::new(R / 2.0 - mu * R, side * 3.0_f32.sqrt() / 2.0 * R);
+
+        for (dr, dtheta) in [(0.0, 0.0), (6.0, 0.03), (-6.0, -0.03), (3.0, -0.06), (-3.0, 0.06)].iter() { //a small swarm around each point
+            let angle = lagrange.y.atan2(lagrange.x) + dtheta;
+            let r = lagrange.norm() + dr;
+            let offset = Vector2::new(angle.cos(), angle.sin()) * r;
+
+            bodies.push(Body::new(center() + offset, 0.01, 0.0, 2.0, co_rotating(offset)));
+        }
+    }
+
+    scene(bodies, 0.05, 200, 1.0)
+}
+
+fn pythagorean() -> Scene {
+    //Burrau's problem: masses 3, 4 and 5 at rest on the corners of a 3-4-5 right triangle
+    let bodies = from_unitless(&[
+        (3.0, [1.0, 3.0], [0.0, 0.0]),
+        (4.0, [-2.0, -1.0], [0.0, 0.0]),
+        (5.0, [1.0, -1.0], [0.0, 0.0]),
+    ], 150.0, 20_000.0, 0.5);
+
+    scene(bodies, 0.002, 1000, 0.7)
+}
+
+fn galaxy() -> Scene {
+    let bodies = generators::galaxy_disk(center(), &DiskParams::default(), G, 1);
+    scene(bodies, 0.02, 60, 1.2)
+}
+
+fn galaxy_collision() -> Scene {
+    let params = CollisionParams {
+        inclination: 30.0_f32.to_radians(),
+        ..CollisionParams::default()
+    };
+
+    let bodies = generators::galaxy_collision(center(), &params, G, 1);
+    scene(bodies, 0.02, 60, 0.6)
+}
+
+fn plummer_cluster() -> Scene {
+    let bodies = generators::plummer(center(), &ClusterParams::default(), G, 1);
+    scene(bodies, 0.02, 60, 1.0)
+}
+
+fn cold_collapse() -> Scene {
+    let params = ClusterParams {
+        scale_radius: 300.0,
+        ..ClusterParams::default()
+    };
+
+    let bodies = generators::cold_collapse(center(), &params, 1);
+    scene(bodies, 0.02, 60, 1.0)
+}
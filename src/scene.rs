@@ -0,0 +1,385 @@
+use nalgebra as na;
+use crate::body::{reserve_id, Body};
+use crate::physics::{Forces, Integrator};
+use crate::simulation::Simulation;
+use crate::units::UnitSystem;
+
+use serde_json::{json, Value};
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+const CSV_HEADER: &str = "x,y,vx,vy,mass,charge,radius";
+
+pub const SCENE_VERSION: u64 = 1;
+
+#[derive(Clone)]
+pub struct Scene {
+    pub sim: Simulation,
+    pub density: f32,
+    pub charge: f32,
+    pub offset: Point2,
+    pub zoom: f32,
+    pub units: UnitSystem, //labels for the numbers, the forces themselves are in sim.forces
+}
+
+impl Scene {
+    pub fn new(sim: Simulation) -> Self {
+        Scene {
+            sim,
+            density: 0.05,
+            charge: 0.0,
+            offset: Point2::new(0.0, 0.0),
+            zoom: 1.0,
+            units: UnitSystem::Sandbox,
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error{
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+pub fn read_csv<R: BufRead>(reader: R) -> io::Result<Vec<Body>>{ //one body per line, same columns as write_csv()
+    let mut bodies = Vec::new();
+
+    for (i, line) in reader.lines().enumerate(){
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == CSV_HEADER {
+            continue;
+        }
+
+        let values = line.split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|e| invalid_data(&format!("line {}: {}", i + 1, e)))?;
+
+        if values.len() != 7 {
+            return Err(invalid_data(&format!("line {}: expected 7 columns ({}), found {}", i + 1, CSV_HEADER, values.len())));
+        }
+
+        bodies.push(Body::new(
+            Point2::new(values[0], values[1]),
+            values[4],
+            values[5],
+            values[6],
+            Vector2::new(values[2], values[3])));
+    }
+
+    Ok(bodies)
+}
+
+pub fn write_csv<W: Write>(mut writer: W, bodies: &[Body]) -> io::Result<()>{
+    writeln!(writer, "{}", CSV_HEADER)?;
+
+    for body in bodies{
+        writeln!(writer, "{},{},{},{},{},{},{}",
+            body.pos.x, body.pos.y,
+            body.velocity.x, body.velocity.y,
+            body.mass, body.charge, body.radius)?;
+    }
+
+    Ok(())
+}
+
+pub fn to_json(scene: &Scene) -> Value{
+    let sim = &scene.sim;
+
+    let bodies = sim.bodies.iter()
+        .map(|body| json!({
+            "id": body.id,
+            "name": body.name,
+            "parents": body.parents.map(|(a, b)| [a, b]),
+            "pos": [body.pos.x, body.pos.y],
+            "velocity": [body.velocity.x, body.velocity.y],
+            "past_accel": [body.past_accel.x, body.past_accel.y], //Verlet's last acceleration, so a loaded scene steps on exactly as it would have
+            "mass": body.mass,
+            "charge": body.charge,
+            "radius": body.radius,
+            "anchored": body.anchored,
+        }))
+        .collect::<Vec<Value>>();
+
+    json!({
+        "version": SCENE_VERSION,
+        "settings": {
+            "integrator": format!("{:?}", sim.integrator),
+            "step_size": sim.step_size,
+            "trail_length": sim.trail_length,
+            "time": sim.time,
+            "merge_times": sim.merge_times,
+            "g": sim.forces.g,
+            "k": sim.forces.k,
+            "density": scene.density,
+            "charge": scene.charge,
+            "units": scene.units.name(),
+        },
+        "camera": {
+            "offset": [scene.offset.x, scene.offset.y],
+            "zoom": scene.zoom,
+        },
+        "bodies": bodies,
+    })
+}
+
+fn get<'a>(value: &'a Value, key: &str) -> io::Result<&'a Value>{
+    value.get(key).ok_or_else(|| invalid_data(&format!("missing field \"{}\"", key)))
+}
+
+fn as_f32(value: &Value, key: &str) -> io::Result<f32>{
+    value.as_f64()
+        .map(|x| x as f32)
+        .ok_or_else(|| invalid_data(&format!("\"{}\" should be a number", key)))
+}
+
+fn as_pair(value: &Value, key: &str) -> io::Result<(f32, f32)>{
+    match value.as_array().map(|pair| pair.as_slice()){
+        Some([x, y]) => Ok((as_f32(x, key)?, as_f32(y, key)?)),
+        _ => Err(invalid_data(&format!("\"{}\" should be an [x, y] pair", key))),
+    }
+}
+
+fn f32_or(value: &Value, key: &str, default: f32) -> io::Result<f32>{ //optional fields fall back to a default
+    value.get(key).map_or(Ok(default), |x| as_f32(x, key))
+}
+
+fn body_from_json(value: &Value) -> io::Result<Body>{
+    let (x, y) = as_pair(get(value, "pos")?, "pos")?;
+    let (vx, vy) = value.get("velocity").map_or(Ok((0.0, 0.0)), |v| as_pair(v, "velocity"))?;
+
+    let mut body = Body::new(
+        Point2::new(x, y),
+        as_f32(get(value, "mass")?, "mass")?,
+        f32_or(value, "charge", 0.0)?,
+        as_f32(get(value, "radius")?, "radius")?,
+        Vector2::new(vx, vy));
+
+    if let Some(past_accel) = value.get("past_accel") {
+        let (ax, ay) = as_pair(past_accel, "past_accel")?;
+        body.past_accel = Vector2::new(ax, ay);
+    }
+    body.anchored = value.get("anchored").and_then(Value::as_bool).unwrap_or(false);
+    body.name = value.get("name").and_then(Value::as_str).map(str::to_string);
+
+    if let Some(id) = value.get("id").and_then(Value::as_u64) { //keep ids stable across saves
+        if id == u64::MAX { //recordings use it for "no parent"
+            return Err(invalid_data("\"id\" can't be the largest u64"));
+        }
+        body.id = id;
+        reserve_id(id);
+    }
+
+    if let Some(parents) = value.get("parents").and_then(Value::as_array) {
+        match parents.as_slice() {
+            [a, b] => body.parents = a.as_u64().zip(b.as_u64()).filter(|(a, b)| *a != u64::MAX && *b != u64::MAX),
+            _ => return Err(invalid_data("\"parents\" should be a pair of ids")),
+        }
+    }
+
+    Ok(body)
+}
+
+pub fn from_json(value: &Value) -> io::Result<Scene>{
+    let version = get(value, "version")?.as_u64().ok_or_else(|| invalid_data("\"version\" should be an integer"))?;
+    if version > SCENE_VERSION {
+        return Err(invalid_data(&format!("scene version {} is newer than supported version {}", version, SCENE_VERSION)));
+    }
+
+    let bodies = get(value, "bodies")?.as_array()
+        .ok_or_else(|| invalid_data("\"bodies\" should be an array"))?
+        .iter()
+        .map(body_from_json)
+        .collect::<io::Result<Vec<Body>>>()?;
+
+    let mut scene = Scene::new(Simulation::new(bodies));
+
+    if let Some(settings) = value.get("settings") {
+        if let Some(units) = settings.get("units").and_then(Value::as_str) {
+            scene.units = units.parse::<UnitSystem>().map_err(|e| invalid_data(&e))?;
+        }
+
+        let sim = &mut scene.sim;
+        let defaults = scene.units.forces(); //G and k are only needed if they differ from the unit system's
+
+        if let Some(integrator) = settings.get("integrator").and_then(Value::as_str) {
+            sim.integrator = integrator.parse::<Integrator>().map_err(|e| invalid_data(&e))?;
+        }
+        if let Some(trail_length) = settings.get("trail_length").and_then(Value::as_u64) {
+            sim.trail_length = trail_length as usize;
+            sim.bodies.iter_mut().for_each(|body| body.trail_length = trail_length as usize); //step() keeps them in line after this
+        }
+
+        sim.step_size = f32_or(settings, "step_size", sim.step_size)?;
+        sim.time = f32_or(settings, "time", 0.0)?;
+        if let Some(merge_times) = settings.get("merge_times").and_then(Value::as_array) {
+            sim.merge_times = merge_times.iter().map(|t| as_f32(t, "merge_times")).collect::<io::Result<Vec<f32>>>()?;
+        }
+        sim.forces = Forces{
+            g: f32_or(settings, "g", defaults.g)?,
+            k: f32_or(settings, "k", defaults.k)?,
+        };
+        scene.density = f32_or(settings, "density", scene.density)?;
+        scene.charge = f32_or(settings, "charge", scene.charge)?;
+    }
+
+    if let Some(camera) = value.get("camera") {
+        if let Some(offset) = camera.get("offset") {
+            let (x, y) = as_pair(offset, "offset")?;
+            scene.offset = Point2::new(x, y);
+        }
+        scene.zoom = f32_or(camera, "zoom", scene.zoom)?;
+    }
+
+    Ok(scene)
+}
+
+fn is_json(path: &Path) -> bool{
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Scene>{ //.json files are full scenes, anything else is read as a csv body list
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+
+    if is_json(path) {
+        let value: Value = serde_json::from_reader(reader)?;
+        from_json(&value)
+    }else {
+        Ok(Scene::new(Simulation::new(read_csv(reader)?)))
+    }
+}
+
+pub fn save<P: AsRef<Path>>(path: P, scene: &Scene) -> io::Result<()>{
+    let path = path.as_ref();
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    if is_json(path) {
+        serde_json::to_writer_pretty(&mut writer, &to_json(scene))?;
+    }else {
+        write_csv(&mut writer, &scene.sim.bodies)?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged_body() -> Body {
+        let mut body = Body::new(Point2::new(1.5, -2.0), 40.0, -3.0, 4.0, Vector2::new(0.25, 0.5)).with_name("Merged");
+        body.parents = Some((7, 8));
+        body.anchored = true;
+        body.past_accel = Vector2::new(0.125, -0.0625);
+        body
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let bodies = vec![merged_body(), Body::new(Point2::new(10.0, 20.0), 1.0, 0.0, 1.0, Vector2::new(0.0, -1.0))];
+        let mut sim = Simulation::new(bodies.clone());
+        sim.integrator = Integrator::Euler;
+        sim.step_size = 0.5;
+        sim.trail_length = 12;
+        sim.time = 42.0;
+        sim.merge_times = vec![3.0, 17.5];
+        sim.forces = Forces{g: 2.0, k: 3.0};
+
+        let mut scene = Scene::new(sim);
+        scene.density = 0.2;
+        scene.offset = Point2::new(-30.0, 60.0);
+        scene.zoom = 2.5;
+
+        let loaded = from_json(&to_json(&scene)).expect("round trip");
+        assert_eq!(loaded.sim.integrator, Integrator::Euler);
+        assert_eq!(loaded.sim.step_size, 0.5);
+        assert_eq!(loaded.sim.trail_length, 12);
+        assert_eq!(loaded.sim.time, 42.0);
+        assert_eq!(loaded.sim.merge_times, vec![3.0, 17.5]);
+        assert_eq!(loaded.sim.forces, Forces{g: 2.0, k: 3.0});
+        assert_eq!(loaded.density, 0.2);
+        assert_eq!(loaded.offset, Point2::new(-30.0, 60.0));
+        assert_eq!(loaded.zoom, 2.5);
+
+        assert_eq!(loaded.sim.bodies.len(), bodies.len());
+        for (loaded, body) in loaded.sim.bodies.iter().zip(bodies.iter()) {
+            assert_eq!(loaded.id, body.id);
+            assert_eq!(loaded.name, body.name);
+            assert_eq!(loaded.parents, body.parents);
+            assert_eq!(loaded.pos, body.pos);
+            assert_eq!(loaded.velocity, body.velocity);
+            assert_eq!(loaded.past_accel, body.past_accel);
+            assert_eq!(loaded.mass, body.mass);
+            assert_eq!(loaded.charge, body.charge);
+            assert_eq!(loaded.radius, body.radius);
+            assert_eq!(loaded.anchored, body.anchored);
+            assert_eq!(loaded.trail_length, 12);
+        }
+    }
+
+    #[test]
+    fn loaded_ids_are_never_reused() {
+        let mut body = merged_body();
+        body.id = crate::body::next_id() + 1000;
+        let json = to_json(&Scene::new(Simulation::new(vec![body.clone()])));
+
+        from_json(&json).expect("load");
+        assert!(crate::body::next_id() > body.id);
+    }
+
+    #[test]
+    fn optional_fields_default() {
+        let json = json!({
+            "version": SCENE_VERSION,
+            "bodies": [{"pos": [1.0, 2.0], "mass": 3.0, "radius": 4.0}],
+        });
+
+        let scene = from_json(&json).expect("minimal scene");
+        let body = &scene.sim.bodies[0];
+        assert_eq!(body.velocity, Vector2::new(0.0, 0.0));
+        assert_eq!(body.name, None);
+        assert_eq!(body.parents, None);
+        assert!(!body.anchored);
+    }
+
+    #[test]
+    fn rejects_bad_scenes() {
+        let newer = json!({"version": SCENE_VERSION + 1, "bodies": []});
+        assert!(from_json(&newer).is_err());
+
+        let parents = json!({
+            "version": SCENE_VERSION,
+            "bodies": [{"pos": [0.0, 0.0], "mass": 1.0, "radius": 1.0, "parents": [1, 2, 3]}],
+        });
+        assert!(from_json(&parents).is_err());
+
+        let reserved_id = json!({
+            "version": SCENE_VERSION,
+            "bodies": [{"pos": [0.0, 0.0], "mass": 1.0, "radius": 1.0, "id": u64::MAX}],
+        });
+        assert!(from_json(&reserved_id).is_err());
+
+        let missing_mass = json!({"version": SCENE_VERSION, "bodies": [{"pos": [0.0, 0.0], "radius": 1.0}]});
+        assert!(from_json(&missing_mass).is_err());
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let bodies = vec![merged_body(), Body::new(Point2::new(10.0, 20.0), 1.0, 0.5, 1.0, Vector2::new(0.0, -1.0))];
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &bodies).expect("write");
+
+        let loaded = read_csv(&csv[..]).expect("read");
+        assert_eq!(loaded.len(), bodies.len());
+        for (loaded, body) in loaded.iter().zip(bodies.iter()) {
+            assert_eq!(loaded.pos, body.pos);
+            assert_eq!(loaded.velocity, body.velocity);
+            assert_eq!((loaded.mass, loaded.charge, loaded.radius), (body.mass, body.charge, body.radius));
+        }
+    }
+}
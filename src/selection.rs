@@ -0,0 +1,49 @@
+use nalgebra as na;
+use crate::body::Body;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+pub fn rectangle(a: Point2, b: Point2) -> Vec<Point2> { //corners of the box dragged from a to b, as a polygon
+    vec![a, Point2::new(b.x, a.y), b, Point2::new(a.x, b.y)]
+}
+
+pub fn in_polygon(point: Point2, polygon: &[Point2]) -> bool { //even-odd rule, so a lasso crossing itself still works
+    let mut inside = false;
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+pub fn copy_group(bodies: &[Body]) -> Vec<Body> { //copies positioned relative to the group's centre, for pasting
+    if bodies.is_empty() {
+        return Vec::new();
+    }
+
+    let center = bodies.iter().fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.pos.coords) / bodies.len() as f32;
+
+    bodies.iter()
+        .map(|body| {
+            let mut copy = body.clone();
+            copy.pos -= center;
+            copy
+        })
+        .collect()
+}
+
+pub fn paste_group(group: &[Body], at: Point2) -> Vec<Body> { //new bodies with their own ids, centred on at
+    group.iter()
+        .map(|body| {
+            let mut pasted = Body::new(at + body.pos.coords, body.mass, body.charge, body.radius, body.velocity);
+            pasted.anchored = body.anchored;
+            pasted.name = body.name.clone();
+            pasted
+        })
+        .collect()
+}
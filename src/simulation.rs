@@ -0,0 +1,229 @@
+use nalgebra as na;
+use crate::body::Body;
+use crate::physics::*;
+
+type Point2 = na::Point2<f32>;
+type Vector2 = na::Vector2<f32>;
+
+#[derive(Clone)]
+pub struct Simulation {
+    pub bodies: Vec<Body>,
+    pub integrator: Integrator,
+    pub step_size: f32,
+    pub forces: Forces,
+    pub trail_length: usize,
+    pub time: f32,
+    pub merge_times: Vec<f32>, //when bodies merged, merges can't be integrated backwards
+}
+
+impl Simulation {
+    pub fn new(bodies: Vec<Body>) -> Self {
+        Simulation {
+            bodies,
+            integrator: Integrator::Verlet,
+            step_size: 1.0,
+            forces: Forces::default(),
+            trail_length: 30,
+            time: 0.0,
+            merge_times: Vec::new(),
+        }
+    }
+
+    pub fn step(&mut self, n: usize){
+        (0..n).for_each(|_i|{
+            let count = self.bodies.len();
+            self.bodies = update_velocities_and_collide(&self.bodies, self.integrator, self.step_size, self.forces);
+            if self.bodies.len() < count {
+                self.merge_times.push(self.time);
+            }
+
+            let trail_length = self.trail_length;
+            self.bodies.iter_mut().for_each(|body| body.trail_length = trail_length);
+
+            self.time += self.step_size;
+        });
+    }
+
+    pub fn is_reversed(&self) -> bool{
+        self.step_size < 0.0
+    }
+
+    pub fn reverse(&mut self){ //negate the step and run backwards from the current state
+        if let Integrator::Verlet = self.integrator {
+            //stored velocities lag a step behind positions, so finish the last step
+            //and cancel the half step the first backwards step will apply
+            let accels = accelerations(&self.bodies, self.forces);
+            let dt = self.step_size;
+
+            self.bodies.iter_mut()
+                .zip(accels)
+                .for_each(|(body, accel)|{
+                    body.velocity += (body.past_accel + accel)/2.0 * dt + accel * dt;
+                    body.past_accel = accel;
+                });
+        }
+
+        self.step_size = -self.step_size;
+
+        let time = self.time;
+        self.merge_times.retain(|t| *t < time); //merges ahead of the current time belong to the abandoned direction
+    }
+
+    pub fn irreversible_merge(&self) -> Option<f32>{ //a merge that running backwards has reached or caused
+        if !self.is_reversed() {
+            return None;
+        }
+
+        self.merge_times.iter()
+            .cloned()
+            .filter(|t| *t >= self.time)
+            .fold(None, |latest: Option<f32>, t| Some(latest.map_or(t, |latest| latest.max(t))))
+    }
+
+    pub fn add_body(&mut self, body: Body){
+        self.bodies.push(body);
+    }
+
+    pub fn body_at(&self, point: Point2) -> Option<usize>{ //index of the first body under point
+        self.bodies.iter().position(|body| distance(point, body.pos) <= body.radius)
+    }
+
+    pub fn remove_bodies_at(&mut self, point: Point2) -> Vec<Body>{ //returns the removed bodies
+        let (removed, kept) = self.bodies.drain(..).partition(|body| distance(point, body.pos) <= body.radius);
+        self.bodies = kept;
+        removed
+    }
+
+    pub fn remove_bodies(&mut self, ids: &[u64]) -> Vec<Body>{
+        let (removed, kept) = self.bodies.drain(..).partition(|body| ids.contains(&body.id));
+        self.bodies = kept;
+        removed
+    }
+
+    pub fn total_mass(&self) -> f32{
+        self.bodies.iter().map(|body| body.mass).sum()
+    }
+
+    pub fn barycenter(&self) -> Point2{
+        let total_mass = self.total_mass();
+        if total_mass == 0.0 {
+            return Point2::new(0.0, 0.0);
+        }
+
+        let weighted = self.bodies.iter()
+            .fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.pos.coords * body.mass);
+
+        Point2::from(weighted / total_mass)
+    }
+
+    pub fn momentum(&self) -> Vector2{
+        self.bodies.iter()
+            .fold(Vector2::new(0.0, 0.0), |acc, body| acc + body.velocity * body.mass)
+    }
+
+    pub fn kinetic_energy(&self) -> f32{
+        self.bodies.iter()
+            .map(|body| 0.5 * body.mass * body.velocity.norm_squared())
+            .sum()
+    }
+
+    pub fn potential_energy(&self) -> f32{ //gravitational and electric potential energy of every pair
+        let mut energy = 0.0;
+
+        for (i, body1) in self.bodies.iter().enumerate(){
+            for body2 in self.bodies.iter().skip(i + 1){
+                let r = distance(body1.pos, body2.pos);
+                if r > 0.0 {
+                    energy += (self.forces.k * body1.charge * body2.charge - self.forces.g * body1.mass * body2.mass) / r;
+                }
+            }
+        }
+
+        energy
+    }
+
+    pub fn total_energy(&self) -> f32{
+        self.kinetic_energy() + self.potential_energy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit() -> Simulation { //a light body on a roughly circular orbit, far enough out not to merge
+        let g = Forces::default().g;
+        let speed = (g * 10_000.0 / 200.0).sqrt();
+        let mut sim = Simulation::new(vec![
+            Body::new(Point2::new(0.0, 0.0), 10_000.0, 0.0, 10.0, Vector2::new(0.0, 0.0)),
+            Body::new(Point2::new(200.0, 0.0), 1.0, 0.0, 2.0, Vector2::new(0.0, speed)),
+        ]);
+        sim.step_size = 0.1;
+        sim
+    }
+
+    fn run_back(integrator: Integrator, steps: usize) -> (Simulation, Vec<Simulation>) { //the world after going forward and back, and the forward run's states
+        let mut sim = orbit();
+        sim.integrator = integrator;
+
+        let mut forward = vec![sim.clone()];
+        for _ in 0..steps {
+            sim.step(1);
+            forward.push(sim.clone());
+        }
+
+        sim.reverse();
+        sim.step(steps);
+        (sim, forward)
+    }
+
+    #[test]
+    fn verlet_retraces_its_path() {
+        let (sim, forward) = run_back(Integrator::Verlet, 500);
+        assert!((forward[500].bodies[1].pos - forward[0].bodies[1].pos).norm() > 50.0, "the body should have moved along its orbit");
+        assert!(sim.time.abs() < 1e-3);
+
+        //stored velocities lag a step behind positions, going backwards the lag is the other way,
+        //so back at the start the velocity is the one the forward run had after its second step
+        for (i, body) in sim.bodies.iter().enumerate() {
+            let (start, second) = (&forward[0].bodies[i], &forward[2].bodies[i]);
+            assert!((body.pos - start.pos).norm() < 0.01, "ended at {} instead of {}", body.pos, start.pos);
+            assert!((body.velocity - second.velocity).norm() < 0.01, "velocity {} instead of {}", body.velocity, second.velocity);
+        }
+
+        //and going forward again retraces the original run
+        let mut again = sim.clone();
+        again.reverse();
+        again.step(100);
+        assert!((again.bodies[1].pos - forward[100].bodies[1].pos).norm() < 0.01);
+    }
+
+    #[test]
+    fn euler_drifts_when_reversed() {
+        let (sim, forward) = run_back(Integrator::Euler, 500);
+        let error = (sim.bodies[1].pos - forward[0].bodies[1].pos).norm();
+        assert!(error > 0.1, "Euler isn't time-reversible, but came back within {}", error);
+    }
+
+    #[test]
+    fn merges_are_recorded() {
+        let mut sim = Simulation::new(vec![
+            Body::new(Point2::new(0.0, 0.0), 10.0, 0.0, 5.0, Vector2::new(0.0, 0.0)),
+            Body::new(Point2::new(6.0, 0.0), 1.0, 0.0, 2.0, Vector2::new(0.0, 0.0)),
+        ]);
+        let ids = (sim.bodies[0].id, sim.bodies[1].id);
+
+        sim.step(1);
+        assert_eq!(sim.bodies.len(), 1);
+        assert_eq!(sim.merge_times, vec![0.0]);
+        assert_eq!(sim.bodies[0].mass, 11.0);
+
+        let parents = sim.bodies[0].parents.expect("a merged body has parents");
+        assert!(parents == ids || parents == (ids.1, ids.0));
+
+        sim.reverse();
+        assert_eq!(sim.irreversible_merge(), None);
+        sim.step(1); //back to when they merged
+        assert_eq!(sim.irreversible_merge(), Some(0.0));
+    }
+}
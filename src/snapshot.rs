@@ -0,0 +1,62 @@
+use crate::scene::{self, Scene};
+use crate::simulation::Simulation;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub const SLOTS: usize = 9;
+
+#[derive(Clone, Default)]
+pub struct Snapshots {
+    slots: [Option<Simulation>; SLOTS],
+    dir: Option<PathBuf>, //if set, slots are also written here so they survive restarts, everything but the trails
+}
+
+impl Snapshots {
+    pub fn new() -> Self {
+        Snapshots::default()
+    }
+
+    pub fn persistent(dir: PathBuf) -> Self { //loads any slots saved by a previous session
+        let mut snapshots = Snapshots {
+            dir: Some(dir),
+            ..Snapshots::default()
+        };
+
+        for slot in 0..SLOTS {
+            let path = snapshots.slot_path(slot).expect("persistent snapshots have a directory");
+            if path.exists() {
+                match scene::load(&path) {
+                    Ok(scene) => snapshots.slots[slot] = Some(scene.sim),
+                    Err(e) => println!("Error loading snapshot {}: {}", path.display(), e),
+                }
+            }
+        }
+
+        snapshots
+    }
+
+    fn slot_path(&self, slot: usize) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(format!("slot{}.json", slot + 1)))
+    }
+
+    pub fn save(&mut self, slot: usize, sim: &Simulation) -> io::Result<()> {
+        self.slots[slot] = Some(sim.clone());
+
+        if let (Some(dir), Some(path)) = (&self.dir, self.slot_path(slot)) {
+            fs::create_dir_all(dir)?;
+            scene::save(path, &Scene::new(sim.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn restore(&self, slot: usize) -> Option<Simulation> {
+        self.slots[slot].clone()
+    }
+
+    pub fn is_filled(&self, slot: usize) -> bool {
+        self.slots[slot].is_some()
+    }
+}
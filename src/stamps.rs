@@ -0,0 +1,53 @@
+use crate::body::Body;
+use crate::scene::{self, Scene};
+use crate::simulation::Simulation;
+use crate::snapshot::SLOTS;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub struct Stamps { //groups of bodies saved for pasting again, kept on disk so they carry over between sessions
+    slots: [Option<Vec<Body>>; SLOTS],
+    dir: PathBuf,
+}
+
+impl Stamps {
+    pub fn load(dir: PathBuf) -> Self { //reads any stamps saved by a previous session
+        let mut stamps = Stamps {
+            slots: Default::default(),
+            dir,
+        };
+
+        for slot in 0..SLOTS {
+            let path = stamps.slot_path(slot);
+            if path.exists() {
+                match scene::load(&path) {
+                    Ok(scene) => stamps.slots[slot] = Some(scene.sim.bodies),
+                    Err(e) => println!("Error loading stamp {}: {}", path.display(), e),
+                }
+            }
+        }
+
+        stamps
+    }
+
+    fn slot_path(&self, slot: usize) -> PathBuf {
+        self.dir.join(format!("stamp{}.json", slot + 1))
+    }
+
+    pub fn save(&mut self, slot: usize, group: Vec<Body>) -> io::Result<()> { //group as made by selection::copy_group
+        fs::create_dir_all(&self.dir)?;
+        scene::save(self.slot_path(slot), &Scene::new(Simulation::new(group.clone())))?;
+        self.slots[slot] = Some(group);
+        Ok(())
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&[Body]> {
+        self.slots[slot].as_deref()
+    }
+
+    pub fn is_filled(&self, slot: usize) -> bool {
+        self.slots[slot].is_some()
+    }
+}
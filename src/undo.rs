@@ -0,0 +1,76 @@
+use crate::body::Body;
+use crate::patterns::Pattern;
+use crate::physics::{Forces, Integrator};
+use crate::units::UnitSystem;
+
+const UNDO_LIMIT: usize = 200;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Params{ //every setting the keyboard can change
+    pub density: f32,
+    pub radius: f32,
+    pub charge: f32,
+    pub trail_length: usize,
+    pub predict_speed: usize,
+    pub forecast_steps: usize,
+    pub fast_forward: usize,
+    pub step_size: f32, //size only, the direction changes through Simulation::reverse()
+    pub integrator: Integrator,
+    pub pattern: Pattern,
+    pub units: UnitSystem,
+    pub forces: Forces,
+}
+
+#[derive(Clone)]
+pub enum Edit{
+    AddBodies(Vec<Body>),
+    RemoveBodies(Vec<Body>),
+    Params{before: Params, after: Params},
+    ChangeBodies{before: Vec<Body>, after: Vec<Body>}, //same ids, bodies that merged since the edit are skipped
+}
+
+impl Edit{
+    pub fn inverse(&self) -> Edit{
+        match self{
+            Edit::AddBodies(bodies) => Edit::RemoveBodies(bodies.clone()),
+            Edit::RemoveBodies(bodies) => Edit::AddBodies(bodies.clone()),
+            Edit::Params{before, after} => Edit::Params{before: *after, after: *before},
+            Edit::ChangeBodies{before, after} => Edit::ChangeBodies{before: after.clone(), after: before.clone()},
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UndoStack{
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl UndoStack{
+    pub fn push(&mut self, edit: Edit){
+        self.redo.clear();
+        self.undo.push(edit);
+
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<Edit>{ //returns the edit that reverts the last action
+        let edit = self.undo.pop()?;
+        let inverse = edit.inverse();
+        self.redo.push(edit);
+        Some(inverse)
+    }
+
+    pub fn redo(&mut self) -> Option<Edit>{
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    pub fn clear(&mut self){
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -0,0 +1,90 @@
+use crate::physics::{Forces, G, K};
+
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UnitSystem{ //what one unit of length, mass, time and charge means, positions are always 1 unit per pixel at zoom 1
+    Sandbox,
+    Si,
+    Astronomical, //AU, solar masses and years
+}
+
+impl UnitSystem{
+    pub fn next(self) -> UnitSystem{
+        match self{
+            UnitSystem::Sandbox => UnitSystem::Si,
+            UnitSystem::Si => UnitSystem::Astronomical,
+            UnitSystem::Astronomical => UnitSystem::Sandbox,
+        }
+    }
+
+    pub fn name(&self) -> &'static str{
+        match self{
+            UnitSystem::Sandbox => "sandbox",
+            UnitSystem::Si => "si",
+            UnitSystem::Astronomical => "astronomical",
+        }
+    }
+
+    pub fn forces(&self) -> Forces{ //G and k in this system
+        match self{
+            UnitSystem::Sandbox => Forces{g: G, k: K},
+            UnitSystem::Si => Forces{g: 6.674e-11, k: 8.988e9},
+            //Kepler's third law is T^2 = a^3 with G = 4 pi^2, charges stay in coulombs
+            UnitSystem::Astronomical => Forces{g: 4.0 * PI * PI, k: 1.344e-39},
+        }
+    }
+
+    pub fn length(&self) -> &'static str{
+        match self{
+            UnitSystem::Sandbox => "px",
+            UnitSystem::Si => "m",
+            UnitSystem::Astronomical => "AU",
+        }
+    }
+
+    pub fn mass(&self) -> &'static str{
+        match self{
+            UnitSystem::Sandbox => "mu",
+            UnitSystem::Si => "kg",
+            UnitSystem::Astronomical => "Msun",
+        }
+    }
+
+    pub fn time(&self) -> &'static str{
+        match self{
+            UnitSystem::Sandbox => "t",
+            UnitSystem::Si => "s",
+            UnitSystem::Astronomical => "yr",
+        }
+    }
+
+    pub fn charge(&self) -> &'static str{
+        match self{
+            UnitSystem::Sandbox => "q",
+            UnitSystem::Si | UnitSystem::Astronomical => "C",
+        }
+    }
+
+    pub fn velocity(&self) -> String{
+        format!("{}/{}", self.length(), self.time())
+    }
+
+    pub fn density(&self) -> String{
+        format!("{}/{}^3", self.mass(), self.length())
+    }
+}
+
+impl FromStr for UnitSystem{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>{
+        match s.to_lowercase().as_str(){
+            "sandbox" => Ok(UnitSystem::Sandbox),
+            "si" => Ok(UnitSystem::Si),
+            "astronomical" => Ok(UnitSystem::Astronomical),
+            _ => Err(format!("unknown unit system \"{}\"", s)),
+        }
+    }
+}